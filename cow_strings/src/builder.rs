@@ -0,0 +1,145 @@
+use std::{borrow::Cow, fmt};
+
+use crate::ToCow;
+
+// Collects segments without copying them and only concatenates once, in
+// `build`. Text written through `fmt::Write` is not 'static, so it is copied
+// into a trailing owned segment that consecutive writes keep appending to.
+#[derive(Clone, Debug, Default)]
+pub struct CowBuilder {
+    segments: Vec<Cow<'static, str>>,
+    len: usize,
+}
+
+impl CowBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(segments: usize) -> Self {
+        Self {
+            segments: Vec::with_capacity(segments),
+            len: 0,
+        }
+    }
+
+    pub fn push<S: ToCow>(&mut self, segment: S) -> &mut Self {
+        let segment = segment.cow();
+        if !segment.is_empty() {
+            self.len += segment.len();
+            self.segments.push(segment);
+        }
+        self
+    }
+
+    pub fn push_line<S: ToCow>(&mut self, segment: S) -> &mut Self {
+        if !self.is_empty() {
+            self.push("\n");
+        }
+        self.push(segment)
+    }
+
+    pub fn append(&mut self, other: CowBuilder) -> &mut Self {
+        self.len += other.len;
+        self.segments.extend(other.segments);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn chunks(&self) -> impl Iterator<Item = &str> {
+        self.segments.iter().map(|segment| segment.as_ref())
+    }
+
+    pub fn into_chunks(self) -> std::vec::IntoIter<Cow<'static, str>> {
+        self.segments.into_iter()
+    }
+
+    pub fn build(self) -> String {
+        let mut segments = self.segments.into_iter();
+        let mut built = match segments.next() {
+            Some(Cow::Owned(first)) => first,
+            Some(Cow::Borrowed(first)) => first.to_string(),
+            None => return String::new(),
+        };
+        built.reserve_exact(self.len - built.len());
+        for segment in segments {
+            built.push_str(&segment);
+        }
+        built
+    }
+
+    pub fn build_cow(mut self) -> Cow<'static, str> {
+        match self.segments.len() {
+            0 => Cow::Borrowed(""),
+            1 => self.segments.pop().unwrap(),
+            _ => Cow::Owned(self.build()),
+        }
+    }
+}
+
+impl fmt::Write for CowBuilder {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if s.is_empty() {
+            return Ok(());
+        }
+        self.len += s.len();
+        match self.segments.last_mut() {
+            Some(Cow::Owned(last)) => last.push_str(s),
+            _ => self.segments.push(Cow::Owned(s.to_string())),
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for CowBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for chunk in self.chunks() {
+            f.write_str(chunk)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<CowBuilder> for String {
+    fn from(builder: CowBuilder) -> Self {
+        builder.build()
+    }
+}
+
+impl From<CowBuilder> for Cow<'static, str> {
+    fn from(builder: CowBuilder) -> Self {
+        builder.build_cow()
+    }
+}
+
+impl<S: ToCow> Extend<S> for CowBuilder {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, iter: I) {
+        for segment in iter {
+            self.push(segment);
+        }
+    }
+}
+
+impl<S: ToCow> FromIterator<S> for CowBuilder {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        let mut builder = Self::new();
+        builder.extend(iter);
+        builder
+    }
+}
+
+impl IntoIterator for CowBuilder {
+    type Item = Cow<'static, str>;
+    type IntoIter = std::vec::IntoIter<Cow<'static, str>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_chunks()
+    }
+}
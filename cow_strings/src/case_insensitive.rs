@@ -0,0 +1,253 @@
+use std::{
+    borrow::{Borrow, Cow},
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    ops::Deref,
+};
+
+use caseless::Caseless;
+use unicode_normalization::UnicodeNormalization;
+
+// How strings are folded before being compared or hashed. Folding is done
+// lazily on `char` iterators so lookups never allocate a folded copy.
+pub trait Folding {
+    fn fold(s: &str) -> impl Iterator<Item = char> + '_;
+}
+
+// Unicode default case folding ("Straße" == "STRASSE").
+#[derive(Clone, Copy, Debug)]
+pub enum CaseFold {}
+
+impl Folding for CaseFold {
+    fn fold(s: &str) -> impl Iterator<Item = char> + '_ {
+        s.chars().default_case_fold()
+    }
+}
+
+// Compatibility caseless matching (NFKC_Casefold equivalence), so that e.g.
+// "ﬁle" and fullwidth "ＦＩＬＥ" both match "file".
+#[derive(Clone, Copy, Debug)]
+pub enum NfkcCaseFold {}
+
+impl Folding for NfkcCaseFold {
+    fn fold(s: &str) -> impl Iterator<Item = char> + '_ {
+        s.chars()
+            .nfd()
+            .default_case_fold()
+            .nfkd()
+            .default_case_fold()
+            .nfkd()
+    }
+}
+
+// Unsized borrowed form, used to look up `CaseInsensitive` keys by `&str`:
+// `map.get(CaseInsensitiveStr::new("Chrome"))`.
+#[repr(transparent)]
+pub struct CaseInsensitiveStr<F = CaseFold> {
+    fold: PhantomData<fn() -> F>,
+    inner: str,
+}
+
+impl<F> CaseInsensitiveStr<F> {
+    pub fn new(s: &str) -> &Self {
+        // SAFETY: `Self` is `repr(transparent)` over `str`.
+        unsafe { &*(s as *const str as *const Self) }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.inner
+    }
+}
+
+impl<F: Folding> PartialEq for CaseInsensitiveStr<F> {
+    fn eq(&self, other: &Self) -> bool {
+        F::fold(&self.inner).eq(F::fold(&other.inner))
+    }
+}
+
+impl<F: Folding> Eq for CaseInsensitiveStr<F> {}
+
+impl<F: Folding> PartialOrd for CaseInsensitiveStr<F> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<F: Folding> Ord for CaseInsensitiveStr<F> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        F::fold(&self.inner).cmp(F::fold(&other.inner))
+    }
+}
+
+impl<F: Folding> Hash for CaseInsensitiveStr<F> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for c in F::fold(&self.inner) {
+            state.write_u32(c as u32);
+        }
+        state.write_u8(0xff);
+    }
+}
+
+impl<F> fmt::Debug for CaseInsensitiveStr<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.inner, f)
+    }
+}
+
+impl<F> fmt::Display for CaseInsensitiveStr<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.inner, f)
+    }
+}
+
+impl<F> AsRef<str> for CaseInsensitiveStr<F> {
+    fn as_ref(&self) -> &str {
+        &self.inner
+    }
+}
+
+// Keeps the original string as is; only comparisons, ordering and hashing
+// look at the folded form.
+pub struct CaseInsensitive<S, F = CaseFold> {
+    inner: S,
+    fold: PhantomData<fn() -> F>,
+}
+
+pub type NormalizedCaseInsensitive<S> = CaseInsensitive<S, NfkcCaseFold>;
+
+pub type CaseInsensitiveCow = CaseInsensitive<Cow<'static, str>>;
+
+impl<S, F> CaseInsensitive<S, F> {
+    pub const fn new(inner: S) -> Self {
+        Self {
+            inner,
+            fold: PhantomData,
+        }
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+}
+
+impl<S: AsRef<str>, F> CaseInsensitive<S, F> {
+    pub fn as_str(&self) -> &str {
+        self.inner.as_ref()
+    }
+}
+
+impl<S: AsRef<str>, F> Deref for CaseInsensitive<S, F> {
+    type Target = CaseInsensitiveStr<F>;
+
+    fn deref(&self) -> &Self::Target {
+        CaseInsensitiveStr::new(self.inner.as_ref())
+    }
+}
+
+impl<S: AsRef<str>, F> Borrow<CaseInsensitiveStr<F>> for CaseInsensitive<S, F> {
+    fn borrow(&self) -> &CaseInsensitiveStr<F> {
+        self
+    }
+}
+
+impl<S: AsRef<str>, F> AsRef<str> for CaseInsensitive<S, F> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<S, F> From<S> for CaseInsensitive<S, F> {
+    fn from(inner: S) -> Self {
+        Self::new(inner)
+    }
+}
+
+impl<S: Clone, F> Clone for CaseInsensitive<S, F> {
+    fn clone(&self) -> Self {
+        Self::new(self.inner.clone())
+    }
+}
+
+impl<S: Copy, F> Copy for CaseInsensitive<S, F> {}
+
+impl<S: Default, F> Default for CaseInsensitive<S, F> {
+    fn default() -> Self {
+        Self::new(S::default())
+    }
+}
+
+impl<S: AsRef<str>, F: Folding> PartialEq for CaseInsensitive<S, F> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<S: AsRef<str>, F: Folding> Eq for CaseInsensitive<S, F> {}
+
+impl<S: AsRef<str>, F: Folding> PartialEq<str> for CaseInsensitive<S, F> {
+    fn eq(&self, other: &str) -> bool {
+        **self == *CaseInsensitiveStr::new(other)
+    }
+}
+
+impl<S: AsRef<str>, F: Folding> PartialEq<&str> for CaseInsensitive<S, F> {
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+
+impl<S: AsRef<str>, F: Folding> PartialOrd for CaseInsensitive<S, F> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S: AsRef<str>, F: Folding> Ord for CaseInsensitive<S, F> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+impl<S: AsRef<str>, F: Folding> Hash for CaseInsensitive<S, F> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+impl<S: fmt::Debug, F> fmt::Debug for CaseInsensitive<S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.inner, f)
+    }
+}
+
+impl<S: fmt::Display, F> fmt::Display for CaseInsensitive<S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.inner, f)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<S: serde::Serialize, F> serde::Serialize for CaseInsensitive<S, F> {
+    fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+    where
+        Ser: serde::Serializer,
+    {
+        self.inner.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, S: serde::Deserialize<'de>, F> serde::Deserialize<'de> for CaseInsensitive<S, F> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        S::deserialize(deserializer).map(Self::new)
+    }
+}
@@ -1,3 +1,13 @@
+pub mod builder;
+pub mod case_insensitive;
+#[cfg(feature = "serde")]
+pub mod serde_borrow;
+pub mod transform;
+pub mod unicode;
+
+pub use builder::CowBuilder;
+pub use case_insensitive::{CaseInsensitive, CaseInsensitiveStr};
+
 use std::borrow::Cow;
 
 pub trait ToCow {
@@ -18,6 +28,6 @@ impl ToCow for String {
 
 impl ToCow for Cow<'static, str> {
     fn cow(self) -> Cow<'static, str> {
-        self.to_owned()
+        self
     }
 }
@@ -0,0 +1,150 @@
+use std::{borrow::Cow, fmt};
+
+use serde::de::{Deserializer, Error, Visitor};
+
+use crate::CowBuilder;
+
+// `#[serde(borrow)]` alone only borrows bare `Cow<'a, str>` fields; use these
+// through `#[serde(borrow, with = "cow_strings::serde_borrow")]` (or its
+// `option`/`vec` submodules) to borrow from the input whenever it allows and
+// fall back to an owned copy when it does not (e.g. escaped JSON strings).
+
+struct CowStrVisitor;
+
+impl<'de> Visitor<'de> for CowStrVisitor {
+    type Value = Cow<'de, str>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a string")
+    }
+
+    fn visit_borrowed_str<E: Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        Ok(Cow::Borrowed(v))
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(v.to_string()))
+    }
+
+    fn visit_string<E: Error>(self, v: String) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(v))
+    }
+
+    fn visit_borrowed_bytes<E: Error>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+        std::str::from_utf8(v)
+            .map(Cow::Borrowed)
+            .map_err(|_| Error::invalid_value(serde::de::Unexpected::Bytes(v), &self))
+    }
+
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        std::str::from_utf8(v)
+            .map(|s| Cow::Owned(s.to_string()))
+            .map_err(|_| Error::invalid_value(serde::de::Unexpected::Bytes(v), &self))
+    }
+}
+
+pub fn serialize<S>(value: &str, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_str(value)
+}
+
+pub fn deserialize<'de: 'a, 'a, D>(deserializer: D) -> Result<Cow<'a, str>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_str(CowStrVisitor)
+}
+
+struct Borrowed<'a>(Cow<'a, str>);
+
+impl<'de: 'a, 'a> serde::Deserialize<'de> for Borrowed<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize(deserializer).map(Borrowed)
+    }
+}
+
+pub mod option {
+    use std::borrow::Cow;
+
+    use serde::Deserialize;
+
+    pub fn serialize<S>(value: &Option<Cow<'_, str>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match value {
+            Some(value) => serializer.serialize_some(value.as_ref()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de: 'a, 'a, D>(deserializer: D) -> Result<Option<Cow<'a, str>>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Ok(Option::<super::Borrowed<'a>>::deserialize(deserializer)?.map(|b| b.0))
+    }
+}
+
+pub mod vec {
+    use std::{borrow::Cow, fmt, marker::PhantomData};
+
+    use serde::de::{SeqAccess, Visitor};
+
+    pub fn serialize<S>(value: &[Cow<'_, str>], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(value.iter().map(|s| s.as_ref()))
+    }
+
+    pub fn deserialize<'de: 'a, 'a, D>(deserializer: D) -> Result<Vec<Cow<'a, str>>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct VecVisitor<'a>(PhantomData<Cow<'a, str>>);
+
+        impl<'de: 'a, 'a> Visitor<'de> for VecVisitor<'a> {
+            type Value = Vec<Cow<'a, str>>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a sequence of strings")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+                while let Some(super::Borrowed(value)) = seq.next_element()? {
+                    values.push(value);
+                }
+                Ok(values)
+            }
+        }
+
+        deserializer.deserialize_seq(VecVisitor(PhantomData))
+    }
+}
+
+impl serde::Serialize for CowBuilder {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for CowBuilder {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut builder = CowBuilder::new();
+        builder.push(String::deserialize(deserializer)?);
+        Ok(builder)
+    }
+}
@@ -0,0 +1,175 @@
+use std::borrow::Cow;
+
+pub fn trim(s: Cow<'_, str>) -> Cow<'_, str> {
+    match s {
+        Cow::Borrowed(b) => Cow::Borrowed(b.trim()),
+        Cow::Owned(o) => {
+            let trimmed = o.trim();
+            if trimmed.len() == o.len() {
+                Cow::Owned(o)
+            } else {
+                Cow::Owned(trimmed.to_string())
+            }
+        }
+    }
+}
+
+pub fn trim_start(s: Cow<'_, str>) -> Cow<'_, str> {
+    match s {
+        Cow::Borrowed(b) => Cow::Borrowed(b.trim_start()),
+        Cow::Owned(mut o) => {
+            let start = o.len() - o.trim_start().len();
+            o.drain(..start);
+            Cow::Owned(o)
+        }
+    }
+}
+
+pub fn trim_end(s: Cow<'_, str>) -> Cow<'_, str> {
+    match s {
+        Cow::Borrowed(b) => Cow::Borrowed(b.trim_end()),
+        Cow::Owned(mut o) => {
+            let end = o.trim_end().len();
+            o.truncate(end);
+            Cow::Owned(o)
+        }
+    }
+}
+
+pub fn to_lowercase(s: Cow<'_, str>) -> Cow<'_, str> {
+    if s.chars().flat_map(char::to_lowercase).eq(s.chars()) {
+        s
+    } else {
+        Cow::Owned(s.to_lowercase())
+    }
+}
+
+pub fn to_uppercase(s: Cow<'_, str>) -> Cow<'_, str> {
+    if s.chars().flat_map(char::to_uppercase).eq(s.chars()) {
+        s
+    } else {
+        Cow::Owned(s.to_uppercase())
+    }
+}
+
+pub fn replace<'a>(s: Cow<'a, str>, from: &str, to: &str) -> Cow<'a, str> {
+    if from.is_empty() || from == to || !s.contains(from) {
+        s
+    } else {
+        Cow::Owned(s.replace(from, to))
+    }
+}
+
+// Same set as teloxide::utils::html::escape, plus '"' so the output is also
+// safe inside attribute values.
+pub fn escape_html(s: Cow<'_, str>) -> Cow<'_, str> {
+    escape_with(s, |c| match c {
+        '&' => Some("&amp;"),
+        '<' => Some("&lt;"),
+        '>' => Some("&gt;"),
+        '"' => Some("&quot;"),
+        _ => None,
+    })
+}
+
+// Telegram MarkdownV2: teloxide::utils::markdown::escape's set plus `\`,
+// which MarkdownV2 also requires escaping.
+pub fn escape_markdown(s: Cow<'_, str>) -> Cow<'_, str> {
+    escape_with(s, |c| match c {
+        '\\' => Some("\\\\"),
+        '_' => Some("\\_"),
+        '*' => Some("\\*"),
+        '[' => Some("\\["),
+        ']' => Some("\\]"),
+        '(' => Some("\\("),
+        ')' => Some("\\)"),
+        '~' => Some("\\~"),
+        '`' => Some("\\`"),
+        '>' => Some("\\>"),
+        '#' => Some("\\#"),
+        '+' => Some("\\+"),
+        '-' => Some("\\-"),
+        '=' => Some("\\="),
+        '|' => Some("\\|"),
+        '{' => Some("\\{"),
+        '}' => Some("\\}"),
+        '.' => Some("\\."),
+        '!' => Some("\\!"),
+        _ => None,
+    })
+}
+
+fn escape_with<F>(s: Cow<'_, str>, escape: F) -> Cow<'_, str>
+where
+    F: Fn(char) -> Option<&'static str>,
+{
+    let first = match s.char_indices().find(|(_, c)| escape(*c).is_some()) {
+        Some((i, _)) => i,
+        None => return s,
+    };
+
+    let mut escaped = String::with_capacity(s.len() + s.len() / 8 + 8);
+    escaped.push_str(&s[..first]);
+    for c in s[first..].chars() {
+        match escape(c) {
+            Some(replacement) => escaped.push_str(replacement),
+            None => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+// Trims and collapses every run of whitespace into a single ' '.
+pub fn normalize_whitespace(s: Cow<'_, str>) -> Cow<'_, str> {
+    let s = trim(s);
+
+    let mut prev_whitespace = false;
+    let needs_change = s.chars().any(|c| {
+        let bad = c.is_whitespace() && (c != ' ' || prev_whitespace);
+        prev_whitespace = c.is_whitespace();
+        bad
+    });
+    if !needs_change {
+        return s;
+    }
+
+    let mut normalized = String::with_capacity(s.len());
+    for word in s.split_whitespace() {
+        if !normalized.is_empty() {
+            normalized.push(' ');
+        }
+        normalized.push_str(word);
+    }
+    Cow::Owned(normalized)
+}
+
+// Keeps at most `max_chars` chars, `ellipsis` included, when cutting is needed.
+// An ellipsis longer than `max_chars` is itself cut to `max_chars` chars.
+pub fn truncate<'a>(s: Cow<'a, str>, max_chars: usize, ellipsis: &str) -> Cow<'a, str> {
+    let cut = match s.char_indices().nth(max_chars) {
+        Some((i, _)) => i,
+        None => return s,
+    };
+    let ellipsis = match ellipsis.char_indices().nth(max_chars) {
+        Some((i, _)) => &ellipsis[..i],
+        None => ellipsis,
+    };
+
+    let keep = max_chars.saturating_sub(ellipsis.chars().count());
+    let end = s.char_indices().nth(keep).map_or(cut, |(i, _)| i);
+
+    match s {
+        Cow::Borrowed(b) if ellipsis.is_empty() => Cow::Borrowed(&b[..end]),
+        Cow::Owned(mut o) => {
+            o.truncate(end);
+            o.push_str(ellipsis);
+            Cow::Owned(o)
+        }
+        Cow::Borrowed(b) => {
+            let mut truncated = String::with_capacity(end + ellipsis.len());
+            truncated.push_str(&b[..end]);
+            truncated.push_str(ellipsis);
+            Cow::Owned(truncated)
+        }
+    }
+}
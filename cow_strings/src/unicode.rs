@@ -0,0 +1,154 @@
+use std::borrow::Cow;
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+// Telegram limits, counted in UTF-16 code units after entity parsing.
+pub const TELEGRAM_MESSAGE_LIMIT: usize = 4096;
+pub const TELEGRAM_CAPTION_LIMIT: usize = 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LengthUnit {
+    Bytes,
+    Chars,
+    Utf16,
+    Graphemes,
+    // Terminal columns, East Asian wide characters count as two.
+    DisplayWidth,
+}
+
+impl LengthUnit {
+    pub fn measure(self, s: &str) -> usize {
+        match self {
+            LengthUnit::Bytes => s.len(),
+            LengthUnit::Chars => s.chars().count(),
+            LengthUnit::Utf16 => s.encode_utf16().count(),
+            LengthUnit::Graphemes => s.graphemes(true).count(),
+            LengthUnit::DisplayWidth => s.width(),
+        }
+    }
+
+    fn measure_grapheme(self, grapheme: &str) -> usize {
+        match self {
+            LengthUnit::Graphemes => 1,
+            _ => self.measure(grapheme),
+        }
+    }
+}
+
+pub fn measure(s: &str, unit: LengthUnit) -> usize {
+    unit.measure(s)
+}
+
+// Byte index of the longest grapheme-aligned prefix measuring at most `limit`,
+// or None when the whole string already fits.
+fn fitting_prefix_end(s: &str, limit: usize, unit: LengthUnit) -> Option<usize> {
+    let mut used = 0;
+    for (i, grapheme) in s.grapheme_indices(true) {
+        used += unit.measure_grapheme(grapheme);
+        if used > limit {
+            return Some(i);
+        }
+    }
+    None
+}
+
+pub fn truncate_to(s: Cow<'_, str>, limit: usize, unit: LengthUnit) -> Cow<'_, str> {
+    match fitting_prefix_end(&s, limit, unit) {
+        None => s,
+        Some(end) => match s {
+            Cow::Borrowed(b) => Cow::Borrowed(&b[..end]),
+            Cow::Owned(mut o) => {
+                o.truncate(end);
+                Cow::Owned(o)
+            }
+        },
+    }
+}
+
+// Like `truncate_to`, but the result including `ellipsis` stays within `limit`.
+// An ellipsis longer than `limit` is itself cut to `limit`.
+pub fn truncate_to_with_ellipsis<'a>(
+    s: Cow<'a, str>,
+    limit: usize,
+    unit: LengthUnit,
+    ellipsis: &str,
+) -> Cow<'a, str> {
+    if fitting_prefix_end(&s, limit, unit).is_none() {
+        return s;
+    }
+
+    let ellipsis = match fitting_prefix_end(ellipsis, limit, unit) {
+        Some(end) => &ellipsis[..end],
+        None => ellipsis,
+    };
+    let budget = limit.saturating_sub(unit.measure(ellipsis));
+    let end = fitting_prefix_end(&s, budget, unit).unwrap_or(s.len());
+
+    let mut truncated = match s {
+        Cow::Borrowed(b) => String::with_capacity(end + ellipsis.len()) + &b[..end],
+        Cow::Owned(mut o) => {
+            o.truncate(end);
+            o
+        }
+    };
+    truncated.push_str(ellipsis);
+    Cow::Owned(truncated)
+}
+
+pub fn truncate_for_telegram_message(s: Cow<'_, str>) -> Cow<'_, str> {
+    truncate_to(s, TELEGRAM_MESSAGE_LIMIT, LengthUnit::Utf16)
+}
+
+pub fn truncate_for_telegram_caption(s: Cow<'_, str>) -> Cow<'_, str> {
+    truncate_to(s, TELEGRAM_CAPTION_LIMIT, LengthUnit::Utf16)
+}
+
+// Right-pads with spaces up to `width` terminal columns.
+pub fn pad_to_width(s: Cow<'_, str>, width: usize) -> Cow<'_, str> {
+    let current = s.width();
+    if current >= width {
+        return s;
+    }
+
+    let mut padded = s.into_owned();
+    padded.extend(std::iter::repeat_n(' ', width - current));
+    Cow::Owned(padded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn truncated(s: &str, limit: usize, unit: LengthUnit, ellipsis: &str) -> String {
+        truncate_to_with_ellipsis(Cow::Borrowed(s), limit, unit, ellipsis).into_owned()
+    }
+
+    #[test]
+    fn ellipsis_stays_within_the_limit() {
+        assert_eq!(
+            truncated("hello world", 8, LengthUnit::Chars, "..."),
+            "hello..."
+        );
+        assert_eq!(truncated("hello world", 2, LengthUnit::Chars, "..."), "..");
+        assert_eq!(truncated("hello world", 0, LengthUnit::Chars, "..."), "");
+        assert_eq!(truncated("hello", 5, LengthUnit::Chars, "..."), "hello");
+        assert_eq!(
+            truncated("hello world", 4, LengthUnit::Bytes, "\u{2026}"),
+            "h\u{2026}"
+        );
+        assert_eq!(
+            truncated("hello world", 2, LengthUnit::Bytes, "\u{2026}"),
+            "he"
+        );
+        assert_eq!(
+            truncated(
+                "\u{4f60}\u{597d}\u{4f60}\u{597d}",
+                3,
+                LengthUnit::DisplayWidth,
+                "\u{2026}"
+            ),
+            "\u{4f60}\u{2026}"
+        );
+    }
+}
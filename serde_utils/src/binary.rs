@@ -0,0 +1,470 @@
+use std::{fmt, marker::PhantomData};
+
+use serde::de::{Error, Visitor};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    InvalidCharacter { index: usize, character: char },
+    InvalidLength(usize),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidCharacter { index, character } => {
+                write!(f, "invalid character {:?} at {}", character, index)
+            }
+            Self::InvalidLength(len) => write!(f, "invalid length {}", len),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+// A text encoding for bytes. `encode` writes straight into the formatter, so
+// serializers that support it never hold the whole encoded string.
+pub trait Encoding {
+    const EXPECTING: &'static str;
+
+    fn encode(bytes: &[u8], f: &mut fmt::Formatter<'_>) -> fmt::Result;
+
+    fn decode(s: &str) -> Result<Vec<u8>, DecodeError>;
+}
+
+const BASE64_STANDARD: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const BASE64_URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+const fn base64_decode_table(alphabet: &[u8; 64]) -> [u8; 256] {
+    let mut table = [u8::MAX; 256];
+    let mut i = 0;
+    while i < 64 {
+        table[alphabet[i] as usize] = i as u8;
+        i += 1;
+    }
+    table
+}
+
+fn base64_encode(
+    alphabet: &[u8; 64],
+    pad: bool,
+    bytes: &[u8],
+    f: &mut fmt::Formatter<'_>,
+) -> fmt::Result {
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &byte)| n | u32::from(byte) << (16 - 8 * i));
+        let mut out = [b'='; 4];
+        for (i, c) in out.iter_mut().enumerate().take(chunk.len() + 1) {
+            *c = alphabet[(n >> (18 - 6 * i) & 0x3f) as usize];
+        }
+        let len = if pad { 4 } else { chunk.len() + 1 };
+        // Only ASCII from the alphabet and padding.
+        f.write_str(std::str::from_utf8(&out[..len]).map_err(|_| fmt::Error)?)?;
+    }
+    Ok(())
+}
+
+// Padding is optional on input, whichever way the output is written. The
+// bits left over after the last byte must be zero, so every byte string has
+// only one encoding.
+fn base64_decode(table: &[u8; 256], s: &str) -> Result<Vec<u8>, DecodeError> {
+    let data = s.trim_end_matches('=');
+    let padding = s.len() - data.len();
+    if padding > 2 || padding > 0 && !s.len().is_multiple_of(4) || data.len() % 4 == 1 {
+        return Err(DecodeError::InvalidLength(s.len()));
+    }
+    let mut bytes = Vec::with_capacity(data.len() * 3 / 4);
+    let (mut buf, mut bits) = (0u32, 0);
+    for (index, c) in data.char_indices() {
+        let value = u8::try_from(c).map_or(u8::MAX, |c| table[usize::from(c)]);
+        if value == u8::MAX {
+            return Err(DecodeError::InvalidCharacter {
+                index,
+                character: c,
+            });
+        }
+        buf = buf << 6 | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buf >> bits) as u8);
+        }
+    }
+    if buf & ((1 << bits) - 1) != 0 {
+        if let Some((index, character)) = data.char_indices().last() {
+            return Err(DecodeError::InvalidCharacter { index, character });
+        }
+    }
+    Ok(bytes)
+}
+
+macro_rules! base64_encoding {
+    ($name:ident, $alphabet:ident, $pad:literal, $expecting:literal) => {
+        #[derive(Clone, Copy, Debug)]
+        pub enum $name {}
+
+        impl Encoding for $name {
+            const EXPECTING: &'static str = $expecting;
+
+            fn encode(bytes: &[u8], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                base64_encode($alphabet, $pad, bytes, f)
+            }
+
+            fn decode(s: &str) -> Result<Vec<u8>, DecodeError> {
+                const TABLE: [u8; 256] = base64_decode_table($alphabet);
+                base64_decode(&TABLE, s)
+            }
+        }
+    };
+}
+
+base64_encoding!(Base64, BASE64_STANDARD, true, "a base64 string");
+base64_encoding!(Base64Unpadded, BASE64_STANDARD, false, "a base64 string");
+base64_encoding!(Base64Url, BASE64_URL, true, "a URL-safe base64 string");
+base64_encoding!(
+    Base64UrlUnpadded,
+    BASE64_URL,
+    false,
+    "a URL-safe base64 string"
+);
+
+fn hex_encode(digits: &[u8; 16], bytes: &[u8], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for &byte in bytes {
+        let pair = [
+            digits[usize::from(byte >> 4)],
+            digits[usize::from(byte & 0xf)],
+        ];
+        f.write_str(std::str::from_utf8(&pair).map_err(|_| fmt::Error)?)?;
+    }
+    Ok(())
+}
+
+// Either case is accepted on input.
+fn hex_decode(s: &str) -> Result<Vec<u8>, DecodeError> {
+    if let Some((index, character)) = s.char_indices().find(|(_, c)| !c.is_ascii_hexdigit()) {
+        return Err(DecodeError::InvalidCharacter { index, character });
+    }
+    if !s.len().is_multiple_of(2) {
+        return Err(DecodeError::InvalidLength(s.len()));
+    }
+    let digit = |byte: u8| char::from(byte).to_digit(16).unwrap_or_default() as u8;
+    Ok(s.as_bytes()
+        .chunks(2)
+        .map(|pair| digit(pair[0]) << 4 | digit(pair[1]))
+        .collect())
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Hex {}
+
+impl Encoding for Hex {
+    const EXPECTING: &'static str = "a hex string";
+
+    fn encode(bytes: &[u8], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        hex_encode(b"0123456789abcdef", bytes, f)
+    }
+
+    fn decode(s: &str) -> Result<Vec<u8>, DecodeError> {
+        hex_decode(s)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum HexUpper {}
+
+impl Encoding for HexUpper {
+    const EXPECTING: &'static str = "a hex string";
+
+    fn encode(bytes: &[u8], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        hex_encode(b"0123456789ABCDEF", bytes, f)
+    }
+
+    fn decode(s: &str) -> Result<Vec<u8>, DecodeError> {
+        hex_decode(s)
+    }
+}
+
+// A byte buffer that can be built from decoded bytes; fixed-size ones only
+// take exactly `LEN` bytes.
+pub trait FromBytes: Sized {
+    const LEN: Option<usize> = None;
+
+    fn from_bytes(bytes: Vec<u8>) -> Option<Self>;
+}
+
+impl FromBytes for Vec<u8> {
+    fn from_bytes(bytes: Vec<u8>) -> Option<Self> {
+        Some(bytes)
+    }
+}
+
+impl FromBytes for Box<[u8]> {
+    fn from_bytes(bytes: Vec<u8>) -> Option<Self> {
+        Some(bytes.into_boxed_slice())
+    }
+}
+
+impl<const N: usize> FromBytes for [u8; N] {
+    const LEN: Option<usize> = Some(N);
+
+    fn from_bytes(bytes: Vec<u8>) -> Option<Self> {
+        bytes.try_into().ok()
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl FromBytes for bytes::Bytes {
+    fn from_bytes(bytes: Vec<u8>) -> Option<Self> {
+        Some(bytes.into())
+    }
+}
+
+struct Encoder<'a, E>(&'a [u8], PhantomData<fn() -> E>);
+
+impl<E: Encoding> fmt::Display for Encoder<'_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        E::encode(self.0, f)
+    }
+}
+
+struct DecodeVisitor<E, B>(PhantomData<fn() -> (E, B)>);
+
+impl<'de, E: Encoding, B: FromBytes> Visitor<'de> for DecodeVisitor<E, B> {
+    type Value = B;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(E::EXPECTING)?;
+        if let Some(len) = B::LEN {
+            write!(f, " of {} bytes", len)?;
+        }
+        Ok(())
+    }
+
+    fn visit_str<Er: Error>(self, v: &str) -> Result<Self::Value, Er> {
+        let bytes = E::decode(v).map_err(|err| {
+            Er::custom(format_args!(
+                "invalid value: {:?} is not {}: {}",
+                v,
+                E::EXPECTING,
+                err
+            ))
+        })?;
+        let len = bytes.len();
+        B::from_bytes(bytes).ok_or_else(|| Er::invalid_length(len, &self))
+    }
+}
+
+pub fn serialize_with<E, B, S>(value: &B, serializer: S) -> Result<S::Ok, S::Error>
+where
+    E: Encoding,
+    B: AsRef<[u8]> + ?Sized,
+    S: serde::Serializer,
+{
+    serializer.collect_str(&Encoder::<E>(value.as_ref(), PhantomData))
+}
+
+pub fn deserialize_with<'de, E, B, D>(deserializer: D) -> Result<B, D::Error>
+where
+    E: Encoding,
+    B: FromBytes,
+    D: serde::Deserializer<'de>,
+{
+    deserializer.deserialize_str(DecodeVisitor::<E, B>(PhantomData))
+}
+
+// `Vec<u8>`, `[u8; N]`, `bytes::Bytes` or another buffer written as text
+// encoded with `E`.
+pub struct Encoded<E, B = Vec<u8>>(pub B, PhantomData<fn() -> E>);
+
+impl<E, B> Encoded<E, B> {
+    pub const fn new(bytes: B) -> Self {
+        Self(bytes, PhantomData)
+    }
+
+    pub fn into_inner(self) -> B {
+        self.0
+    }
+}
+
+impl<'de, E: Encoding, B: FromBytes> serde::Deserialize<'de> for Encoded<E, B> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserialize_with::<E, B, D>(deserializer).map(Self::new)
+    }
+}
+
+impl<E: Encoding, B: AsRef<[u8]>> serde::Serialize for Encoded<E, B> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serialize_with::<E, B, S>(&self.0, serializer)
+    }
+}
+
+crate::impl_wrapper_traits!(Encoded<E, B>, B);
+
+impl<E, B: Default> Default for Encoded<E, B> {
+    fn default() -> Self {
+        Self::new(B::default())
+    }
+}
+
+macro_rules! encoded_with_module {
+    ($name:ident, $encoding:ty) => {
+        pub mod $name {
+            use super::FromBytes;
+
+            pub fn serialize<B, S>(value: &B, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                B: AsRef<[u8]> + ?Sized,
+                S: serde::Serializer,
+            {
+                super::serialize_with::<$encoding, B, S>(value, serializer)
+            }
+
+            pub fn deserialize<'de, B, D>(deserializer: D) -> Result<B, D::Error>
+            where
+                B: FromBytes,
+                D: serde::Deserializer<'de>,
+            {
+                super::deserialize_with::<$encoding, B, D>(deserializer)
+            }
+
+            pub mod option {
+                use serde::Deserialize;
+
+                use super::super::{Encoded, FromBytes};
+
+                pub fn serialize<B, S>(value: &Option<B>, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    B: AsRef<[u8]>,
+                    S: serde::Serializer,
+                {
+                    match value {
+                        Some(value) => serializer
+                            .serialize_some(&Encoded::<$encoding, &[u8]>::new(value.as_ref())),
+                        None => serializer.serialize_none(),
+                    }
+                }
+
+                pub fn deserialize<'de, B, D>(deserializer: D) -> Result<Option<B>, D::Error>
+                where
+                    B: FromBytes,
+                    D: serde::Deserializer<'de>,
+                {
+                    let value = Option::<Encoded<$encoding, B>>::deserialize(deserializer)?;
+                    Ok(value.map(Encoded::into_inner))
+                }
+            }
+        }
+    };
+}
+
+encoded_with_module!(base64, crate::binary::Base64);
+encoded_with_module!(base64_unpadded, crate::binary::Base64Unpadded);
+encoded_with_module!(base64_url, crate::binary::Base64Url);
+encoded_with_module!(base64_url_unpadded, crate::binary::Base64UrlUnpadded);
+encoded_with_module!(hex, crate::binary::Hex);
+encoded_with_module!(hex_upper, crate::binary::HexUpper);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode<E: Encoding>(bytes: &[u8]) -> String {
+        Encoder::<E>(bytes, PhantomData).to_string()
+    }
+
+    fn round_trip<E: Encoding>() {
+        let bytes: Vec<u8> = (0..=255).rev().collect();
+        for len in 0..=bytes.len() {
+            let encoded = encode::<E>(&bytes[..len]);
+            assert_eq!(
+                E::decode(&encoded).as_deref(),
+                Ok(&bytes[..len]),
+                "{}",
+                encoded
+            );
+        }
+    }
+
+    #[test]
+    fn encodings_round_trip() {
+        round_trip::<Base64>();
+        round_trip::<Base64Unpadded>();
+        round_trip::<Base64Url>();
+        round_trip::<Base64UrlUnpadded>();
+        round_trip::<Hex>();
+        round_trip::<HexUpper>();
+    }
+
+    #[test]
+    fn base64_output() {
+        assert_eq!(encode::<Base64>(b""), "");
+        assert_eq!(encode::<Base64>(b"A"), "QQ==");
+        assert_eq!(encode::<Base64>(b"AB"), "QUI=");
+        assert_eq!(encode::<Base64>(b"ABC"), "QUJD");
+        assert_eq!(encode::<Base64Unpadded>(b"A"), "QQ");
+        assert_eq!(encode::<Base64Unpadded>(b"AB"), "QUI");
+        assert_eq!(encode::<Base64>(&[0xfb, 0xff]), "+/8=");
+        assert_eq!(encode::<Base64Url>(&[0xfb, 0xff]), "-_8=");
+        assert_eq!(encode::<Base64UrlUnpadded>(&[0xfb, 0xff]), "-_8");
+    }
+
+    #[test]
+    fn base64_input() {
+        // Padding is optional either way.
+        assert_eq!(Base64::decode("QQ"), Ok(b"A".to_vec()));
+        assert_eq!(Base64Unpadded::decode("QQ=="), Ok(b"A".to_vec()));
+
+        for s in ["Q", "QUJDQ", "QQ=", "QQ===", "Q===", "QUJD="] {
+            assert_eq!(
+                Base64::decode(s),
+                Err(DecodeError::InvalidLength(s.len())),
+                "{}",
+                s
+            );
+        }
+
+        // Only "QQ==" encodes b"A".
+        assert_eq!(
+            Base64::decode("QR=="),
+            Err(DecodeError::InvalidCharacter {
+                index: 1,
+                character: 'R'
+            })
+        );
+        assert!(Base64::decode("QUJ").is_err());
+        assert!(Base64Unpadded::decode("QR").is_err());
+
+        assert!(Base64::decode("-_8=").is_err());
+        assert!(Base64Url::decode("+/8=").is_err());
+        assert_eq!(
+            Base64::decode("QQ=A"),
+            Err(DecodeError::InvalidCharacter {
+                index: 2,
+                character: '='
+            })
+        );
+    }
+
+    #[test]
+    fn hex_input() {
+        assert_eq!(Hex::decode("DEADbeef"), Ok(vec![0xde, 0xad, 0xbe, 0xef]));
+        assert_eq!(encode::<HexUpper>(&[0xde, 0xad]), "DEAD");
+        assert_eq!(Hex::decode("abc"), Err(DecodeError::InvalidLength(3)));
+        assert_eq!(
+            Hex::decode("0g"),
+            Err(DecodeError::InvalidCharacter {
+                index: 1,
+                character: 'g'
+            })
+        );
+    }
+}
@@ -1,54 +1,156 @@
-use serde::Deserialize;
+use std::{fmt, marker::PhantomData, str::FromStr};
 
+pub mod boolean;
+pub mod delimited;
+pub mod precise;
+pub mod string_parse;
+
+// `impl Deserialize` through `FromStr` for a type or path without generic
+// parameters; see the `DeserializeFromStr` derive for the rest.
 #[macro_export]
 macro_rules! serde_deserialize_from_str {
-    ($type:ident) => {
-        impl<'de> serde::Deserialize<'de> for $type {
-            fn deserialize<D>(
-                deserializer: D,
-            ) -> Result<Self, <D as serde::Deserializer<'de>>::Error>
+    ($type:ty) => {
+        impl<'de> $crate::__private::serde::Deserialize<'de> for $type {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
             where
-                D: serde::Deserializer<'de>,
+                D: $crate::__private::serde::Deserializer<'de>,
             {
-                let s = String::deserialize(deserializer)?;
-                std::str::FromStr::from_str(&s).map_err(serde::de::Error::custom)
+                $crate::enumstrings::deserialize_from_str(deserializer)
             }
         }
     };
 }
 
+// `impl Serialize` through `Display`; see the `SerializeDisplay` derive for
+// generic types.
 #[macro_export]
 macro_rules! serde_serialize_to_string {
-    ($type:ident) => {
-        impl serde::Serialize for $type {
+    ($type:ty) => {
+        impl $crate::__private::serde::Serialize for $type {
             fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
             where
-                S: serde::Serializer,
+                S: $crate::__private::serde::Serializer,
             {
-                let s = self.to_string();
-                serializer.serialize_str(&s)
+                $crate::enumstrings::serialize_to_string(self, serializer)
             }
         }
     };
 }
 
-pub fn deserialize_from_string_parse<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+// The `FromStr` error of `EnumStr` enums.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownVariantError {
+    value: String,
+    accepted: &'static [&'static str],
+}
+
+impl UnknownVariantError {
+    pub fn new(value: &str, accepted: &'static [&'static str]) -> Self {
+        Self {
+            value: value.to_owned(),
+            accepted,
+        }
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn accepted(&self) -> &'static [&'static str] {
+        self.accepted
+    }
+}
+
+impl fmt::Display for UnknownVariantError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown value {:?}, expected one of: ", self.value)?;
+        for (i, accepted) in self.accepted.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            f.write_str(accepted)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for UnknownVariantError {}
+
+struct FromStrVisitor<T> {
+    expecting: &'static str,
+    error: Option<&'static str>,
+    target: PhantomData<fn() -> T>,
+}
+
+// Parses straight from whatever string the deserializer hands over; borrowed
+// and owned strings end up in `visit_str` too, so nothing is copied into a
+// `String` first.
+impl<T> serde::de::Visitor<'_> for FromStrVisitor<T>
 where
-    D: serde::Deserializer<'de>,
-    T: std::str::FromStr + serde::Deserialize<'de>,
-    <T as std::str::FromStr>::Err: std::fmt::Display,
+    T: FromStr,
+    T::Err: fmt::Display,
 {
-    #[derive(serde::Deserialize)]
-    #[serde(untagged)]
-    enum StringOrNumber<T> {
-        String(String),
-        Number(T),
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.expecting)
     }
 
-    match StringOrNumber::<T>::deserialize(deserializer)? {
-        StringOrNumber::String(s) => s.parse::<T>().map_err(serde::de::Error::custom),
-        StringOrNumber::Number(i) => Ok(i),
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        T::from_str(v).map_err(|err| match self.error {
+            Some(template) => E::custom(
+                template
+                    .replace("{value}", v)
+                    .replace("{error}", &err.to_string()),
+            ),
+            None => E::custom(err),
+        })
     }
+
+    fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        match std::str::from_utf8(v) {
+            Ok(s) => self.visit_str(s),
+            Err(_) => Err(E::invalid_value(serde::de::Unexpected::Bytes(v), &self)),
+        }
+    }
+}
+
+pub fn deserialize_from_str<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    deserialize_from_str_with(deserializer, "a string", None)
+}
+
+// `error` replaces the `FromStr` error message; `{value}` and `{error}` in it
+// stand for the input and the original message.
+pub fn deserialize_from_str_with<'de, T, D>(
+    deserializer: D,
+    expecting: &'static str,
+    error: Option<&'static str>,
+) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    deserializer.deserialize_str(FromStrVisitor {
+        expecting,
+        error,
+        target: PhantomData,
+    })
+}
+
+// A number or a numeric string, with `string_parse::Standard` options.
+pub fn deserialize_from_string_parse<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: FromStr + serde::Deserialize<'de>,
+    <T as FromStr>::Err: fmt::Display,
+{
+    string_parse::deserialize_with::<string_parse::Standard, T, D>(deserializer)
 }
 
 pub fn serialize_to_string<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
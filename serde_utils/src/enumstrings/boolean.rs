@@ -0,0 +1,211 @@
+use std::{fmt, marker::PhantomData};
+
+use serde::de::{Error, Unexpected, Visitor};
+
+// How a boolean is written when serializing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Canonical {
+    // `true` / `false`
+    Bool,
+    // `1` / `0`
+    Int,
+    // The first word of `TRUE` / `FALSE`.
+    Str,
+}
+
+// What a boolean field accepts. Real booleans always work; `NUMBERS` adds
+// 0 and 1, and strings are looked up in `TRUE` and `FALSE`. Outside strict
+// mode the lookup ignores ASCII case and surrounding whitespace:
+//
+//     enum Flag {}
+//
+//     impl BoolOptions for Flag {
+//         const TRUE: &'static [&'static str] = &["Y"];
+//         const FALSE: &'static [&'static str] = &["N"];
+//         const CANONICAL: Canonical = Canonical::Str;
+//     }
+pub trait BoolOptions {
+    const TRUE: &'static [&'static str] = &["true", "1", "yes", "y", "on", "t"];
+    const FALSE: &'static [&'static str] = &["false", "0", "no", "n", "off", "f"];
+    const NUMBERS: bool = true;
+    const STRICT: bool = false;
+    const CANONICAL: Canonical = Canonical::Bool;
+}
+
+// Everything in the default vocabulary, in any case.
+#[derive(Clone, Copy, Debug)]
+pub enum Lenient {}
+
+impl BoolOptions for Lenient {}
+
+// Booleans and the exact strings "true" and "false".
+#[derive(Clone, Copy, Debug)]
+pub enum Strict {}
+
+impl BoolOptions for Strict {
+    const TRUE: &'static [&'static str] = &["true"];
+    const FALSE: &'static [&'static str] = &["false"];
+    const NUMBERS: bool = false;
+    const STRICT: bool = true;
+}
+
+fn matches<B: BoolOptions>(words: &[&str], s: &str) -> bool {
+    words.iter().any(|word| {
+        if B::STRICT {
+            *word == s
+        } else {
+            word.eq_ignore_ascii_case(s)
+        }
+    })
+}
+
+struct BoolVisitor<B>(PhantomData<fn() -> B>);
+
+impl<B: BoolOptions> Visitor<'_> for BoolVisitor<B> {
+    type Value = bool;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a boolean")?;
+        if B::NUMBERS {
+            f.write_str(", 0 or 1")?;
+        }
+        let words = B::TRUE.iter().chain(B::FALSE);
+        for (i, word) in words.enumerate() {
+            f.write_str(match i {
+                0 if B::NUMBERS => ", or one of: ",
+                0 => " or one of: ",
+                _ => ", ",
+            })?;
+            write!(f, "{:?}", word)?;
+        }
+        Ok(())
+    }
+
+    fn visit_bool<E: Error>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(v)
+    }
+
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> {
+        match v {
+            0 | 1 if B::NUMBERS => Ok(v == 1),
+            _ => Err(E::invalid_value(Unexpected::Signed(v), &self)),
+        }
+    }
+
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> {
+        match v {
+            0 | 1 if B::NUMBERS => Ok(v == 1),
+            _ => Err(E::invalid_value(Unexpected::Unsigned(v), &self)),
+        }
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        let s = if B::STRICT { v } else { v.trim() };
+        if matches::<B>(B::TRUE, s) {
+            Ok(true)
+        } else if matches::<B>(B::FALSE, s) {
+            Ok(false)
+        } else {
+            Err(E::invalid_value(Unexpected::Str(v), &self))
+        }
+    }
+}
+
+pub fn deserialize_with<'de, B, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    B: BoolOptions,
+    D: serde::Deserializer<'de>,
+{
+    deserializer.deserialize_any(BoolVisitor::<B>(PhantomData))
+}
+
+pub fn serialize_with<B, S>(value: &bool, serializer: S) -> Result<S::Ok, S::Error>
+where
+    B: BoolOptions,
+    S: serde::Serializer,
+{
+    match B::CANONICAL {
+        Canonical::Bool => serializer.serialize_bool(*value),
+        Canonical::Int => serializer.serialize_u8(u8::from(*value)),
+        Canonical::Str => {
+            let words = if *value { B::TRUE } else { B::FALSE };
+            let fallback = if *value { "true" } else { "false" };
+            serializer.serialize_str(words.first().copied().unwrap_or(fallback))
+        }
+    }
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    deserialize_with::<Lenient, D>(deserializer)
+}
+
+pub fn serialize<S>(value: &bool, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serialize_with::<Lenient, S>(value, serializer)
+}
+
+// `Option<bool>`, with null as `None`.
+pub mod option {
+    use std::marker::PhantomData;
+
+    use super::{BoolOptions, Lenient};
+
+    struct Flag<B>(bool, PhantomData<fn() -> B>);
+
+    impl<'de, B: BoolOptions> serde::Deserialize<'de> for Flag<B> {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            super::deserialize_with::<B, D>(deserializer).map(|value| Self(value, PhantomData))
+        }
+    }
+
+    impl<B: BoolOptions> serde::Serialize for Flag<B> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            super::serialize_with::<B, S>(&self.0, serializer)
+        }
+    }
+
+    pub fn deserialize_with<'de, B, D>(deserializer: D) -> Result<Option<bool>, D::Error>
+    where
+        B: BoolOptions,
+        D: serde::Deserializer<'de>,
+    {
+        let value: Option<Flag<B>> = serde::Deserialize::deserialize(deserializer)?;
+        Ok(value.map(|flag| flag.0))
+    }
+
+    pub fn serialize_with<B, S>(value: &Option<bool>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        B: BoolOptions,
+        S: serde::Serializer,
+    {
+        match value {
+            Some(value) => serializer.serialize_some(&Flag::<B>(*value, PhantomData)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<bool>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserialize_with::<Lenient, D>(deserializer)
+    }
+
+    pub fn serialize<S>(value: &Option<bool>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serialize_with::<Lenient, S>(value, serializer)
+    }
+}
@@ -0,0 +1,404 @@
+use std::{
+    borrow::Cow,
+    fmt::{self, Write},
+    marker::PhantomData,
+    str::FromStr,
+};
+
+use serde::{
+    de::{Error, Unexpected, Visitor},
+    ser,
+};
+
+// What to do with an item that is empty (after trimming), as in "a,,b".
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EmptyItems {
+    // Leave it out. Such items are lost on a round trip: `["a", "", "b"]` is
+    // written as "a,,b" and read back as `["a", "b"]`.
+    Skip,
+    // Hand "" to `FromStr` like any other item.
+    Parse,
+    // Fail the whole list.
+    Reject,
+}
+
+// How a list is written as one string. An input that is empty, or only
+// whitespace when trimming, is always an empty list. Without an escape
+// character, items are written as they are and one that contains the
+// separator fails to serialize; with one, the separator and the escape
+// character itself are escaped on output and unescaped on input:
+//
+//     enum Tags {}
+//
+//     impl ListOptions for Tags {
+//         const SEPARATOR: char = ',';
+//         const ESCAPE: Option<char> = Some('\\');
+//     }
+pub trait ListOptions {
+    const SEPARATOR: char;
+    const TRIM: bool = true;
+    const EMPTY_ITEMS: EmptyItems = EmptyItems::Skip;
+    const ESCAPE: Option<char> = None;
+}
+
+// "a,b,c"
+#[derive(Clone, Copy, Debug)]
+pub enum Comma {}
+
+impl ListOptions for Comma {
+    const SEPARATOR: char = ',';
+}
+
+// "1 2 3"; runs of spaces count as one separator.
+#[derive(Clone, Copy, Debug)]
+pub enum Space {}
+
+impl ListOptions for Space {
+    const SEPARATOR: char = ' ';
+}
+
+// "a;b;c"
+#[derive(Clone, Copy, Debug)]
+pub enum Semicolon {}
+
+impl ListOptions for Semicolon {
+    const SEPARATOR: char = ';';
+}
+
+// "a|b|c"
+#[derive(Clone, Copy, Debug)]
+pub enum Pipe {}
+
+impl ListOptions for Pipe {
+    const SEPARATOR: char = '|';
+}
+
+// Splits `s` at unescaped separators, unescaping items only when needed.
+fn split<L: ListOptions>(s: &str) -> Vec<Cow<'_, str>> {
+    let Some(escape) = L::ESCAPE else {
+        return s.split(L::SEPARATOR).map(Cow::Borrowed).collect();
+    };
+    let mut items = Vec::new();
+    let mut item = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == escape {
+            item.extend(chars.next());
+        } else if c == L::SEPARATOR {
+            items.push(Cow::Owned(std::mem::take(&mut item)));
+        } else {
+            item.push(c);
+        }
+    }
+    items.push(Cow::Owned(item));
+    items
+}
+
+// Escapes whatever an item's `Display` writes.
+struct Escaped<'a, 'b, L> {
+    f: &'a mut fmt::Formatter<'b>,
+    escape: char,
+    options: PhantomData<fn() -> L>,
+}
+
+impl<L: ListOptions> Write for Escaped<'_, '_, L> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            if c == L::SEPARATOR || c == self.escape {
+                self.f.write_char(self.escape)?;
+            }
+            self.f.write_char(c)?;
+        }
+        Ok(())
+    }
+}
+
+// Notes whether an item's `Display` writes the separator.
+struct FindSeparator<L>(bool, PhantomData<fn() -> L>);
+
+impl<L: ListOptions> Write for FindSeparator<L> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 |= s.contains(L::SEPARATOR);
+        Ok(())
+    }
+}
+
+// Writes the items of `C` one after another, so serializing never builds
+// the joined string unless the serializer needs one.
+struct Joined<'a, L, C>(&'a C, PhantomData<fn() -> L>);
+
+impl<'a, L, C, T> fmt::Display for Joined<'a, L, C>
+where
+    L: ListOptions,
+    &'a C: IntoIterator<Item = &'a T>,
+    T: fmt::Display + 'a,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, item) in self.0.into_iter().enumerate() {
+            if i > 0 {
+                f.write_char(L::SEPARATOR)?;
+            }
+            match L::ESCAPE {
+                Some(escape) => write!(
+                    Escaped::<L> {
+                        f,
+                        escape,
+                        options: PhantomData,
+                    },
+                    "{}",
+                    item
+                )?,
+                None => write!(f, "{}", item)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+struct DelimitedVisitor<L, C>(PhantomData<fn() -> (L, C)>);
+
+impl<'de, L, C, T> Visitor<'de> for DelimitedVisitor<L, C>
+where
+    L: ListOptions,
+    C: IntoIterator<Item = T> + FromIterator<T>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    type Value = C;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a string of values separated by {:?}", L::SEPARATOR)
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        let whole = if L::TRIM { v.trim() } else { v };
+        if whole.is_empty() {
+            return Ok(C::from_iter(None));
+        }
+        split::<L>(whole)
+            .iter()
+            .map(|item| if L::TRIM { item.trim() } else { item })
+            .filter(|item| !item.is_empty() || L::EMPTY_ITEMS != EmptyItems::Skip)
+            .map(|item| {
+                if item.is_empty() && L::EMPTY_ITEMS == EmptyItems::Reject {
+                    return Err(E::custom(format_args!(
+                        "invalid value: string {:?} has an empty item",
+                        v
+                    )));
+                }
+                T::from_str(item).map_err(|err| {
+                    E::custom(format_args!(
+                        "invalid value: item {:?} in string {:?}: {}",
+                        item, v, err
+                    ))
+                })
+            })
+            .collect()
+    }
+
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        match std::str::from_utf8(v) {
+            Ok(s) => self.visit_str(s),
+            Err(_) => Err(E::invalid_value(Unexpected::Bytes(v), &self)),
+        }
+    }
+}
+
+pub fn deserialize_with<'de, L, C, T, D>(deserializer: D) -> Result<C, D::Error>
+where
+    L: ListOptions,
+    D: serde::Deserializer<'de>,
+    C: IntoIterator<Item = T> + FromIterator<T>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    deserializer.deserialize_str(DelimitedVisitor::<L, C>(PhantomData))
+}
+
+pub fn serialize_with<'a, L, C, T, S>(values: &'a C, serializer: S) -> Result<S::Ok, S::Error>
+where
+    L: ListOptions,
+    S: serde::Serializer,
+    &'a C: IntoIterator<Item = &'a T>,
+    T: fmt::Display + 'a,
+{
+    // Checked up front: `collect_str` has no way to fail from inside
+    // `Display`.
+    if L::ESCAPE.is_none() {
+        for item in values {
+            let mut find = FindSeparator::<L>(false, PhantomData);
+            write!(find, "{}", item).map_err(ser::Error::custom)?;
+            if find.0 {
+                return Err(ser::Error::custom(format_args!(
+                    "item \"{}\" contains the separator {:?}",
+                    item,
+                    L::SEPARATOR
+                )));
+            }
+        }
+    }
+    serializer.collect_str(&Joined::<L, C>(values, PhantomData))
+}
+
+// A `Vec<T>`, `HashSet<T>` or other collection written as one string of
+// items separated as `L` says.
+pub struct Delimited<L, C>(pub C, PhantomData<fn() -> L>);
+
+impl<L, C> Delimited<L, C> {
+    pub const fn new(values: C) -> Self {
+        Self(values, PhantomData)
+    }
+
+    pub fn into_inner(self) -> C {
+        self.0
+    }
+}
+
+impl<'de, L, C, T> serde::Deserialize<'de> for Delimited<L, C>
+where
+    L: ListOptions,
+    C: IntoIterator<Item = T> + FromIterator<T>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserialize_with::<L, C, T, D>(deserializer).map(Self::new)
+    }
+}
+
+impl<L, C, T> serde::Serialize for Delimited<L, C>
+where
+    L: ListOptions,
+    for<'a> &'a C: IntoIterator<Item = &'a T>,
+    T: fmt::Display,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serialize_with::<L, C, T, S>(&self.0, serializer)
+    }
+}
+
+crate::impl_wrapper_traits!(Delimited<L, C>, C);
+
+impl<L, C: Default> Default for Delimited<L, C> {
+    fn default() -> Self {
+        Self::new(C::default())
+    }
+}
+
+macro_rules! delimited_with_module {
+    ($name:ident, $options:ty) => {
+        pub mod $name {
+            use std::{fmt, str::FromStr};
+
+            pub fn serialize<'a, C, T, S>(values: &'a C, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+                &'a C: IntoIterator<Item = &'a T>,
+                T: fmt::Display + 'a,
+            {
+                super::serialize_with::<$options, C, T, S>(values, serializer)
+            }
+
+            pub fn deserialize<'de, C, T, D>(deserializer: D) -> Result<C, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+                C: IntoIterator<Item = T> + FromIterator<T>,
+                T: FromStr,
+                T::Err: fmt::Display,
+            {
+                super::deserialize_with::<$options, C, T, D>(deserializer)
+            }
+        }
+    };
+}
+
+delimited_with_module!(comma, super::Comma);
+delimited_with_module!(space, super::Space);
+delimited_with_module!(semicolon, super::Semicolon);
+delimited_with_module!(pipe, super::Pipe);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    enum Escaping {}
+
+    impl ListOptions for Escaping {
+        const SEPARATOR: char = ',';
+        const ESCAPE: Option<char> = Some('\\');
+    }
+
+    enum Untrimmed {}
+
+    impl ListOptions for Untrimmed {
+        const SEPARATOR: char = ',';
+        const TRIM: bool = false;
+        const EMPTY_ITEMS: EmptyItems = EmptyItems::Parse;
+    }
+
+    enum Strict {}
+
+    impl ListOptions for Strict {
+        const SEPARATOR: char = ',';
+        const EMPTY_ITEMS: EmptyItems = EmptyItems::Reject;
+    }
+
+    fn read<L: ListOptions>(json: &str) -> Result<Vec<String>, serde_json::Error> {
+        serde_json::from_str::<Delimited<L, Vec<String>>>(json).map(Delimited::into_inner)
+    }
+
+    fn write<L: ListOptions>(items: &[&str]) -> Result<String, serde_json::Error> {
+        serde_json::to_string(&Delimited::<L, _>::new(items.to_vec()))
+    }
+
+    #[test]
+    fn splits_and_joins() {
+        assert_eq!(read::<Comma>(r#"" a , b,c ""#).unwrap(), ["a", "b", "c"]);
+        assert_eq!(read::<Space>(r#""1  2 3""#).unwrap(), ["1", "2", "3"]);
+        assert_eq!(write::<Pipe>(&["a", "b"]).unwrap(), r#""a|b""#);
+        assert_eq!(write::<Comma>(&[]).unwrap(), r#""""#);
+
+        let numbers: Delimited<Semicolon, Vec<i32>> = serde_json::from_str(r#""1;-2""#).unwrap();
+        assert_eq!(numbers.0, [1, -2]);
+        assert!(serde_json::from_str::<Delimited<Semicolon, Vec<i32>>>(r#""1;x""#).is_err());
+    }
+
+    #[test]
+    fn empty_input_is_an_empty_list() {
+        assert!(read::<Comma>(r#""""#).unwrap().is_empty());
+        assert!(read::<Comma>(r#""  ""#).unwrap().is_empty());
+        assert!(read::<Strict>(r#""""#).unwrap().is_empty());
+        assert!(read::<Untrimmed>(r#""""#).unwrap().is_empty());
+        assert_eq!(read::<Untrimmed>(r#"" ""#).unwrap(), [" "]);
+    }
+
+    #[test]
+    fn empty_items() {
+        assert_eq!(read::<Comma>(r#""a,,b,""#).unwrap(), ["a", "b"]);
+        assert_eq!(
+            read::<Untrimmed>(r#""a,, b,""#).unwrap(),
+            ["a", "", " b", ""]
+        );
+        assert!(read::<Strict>(r#""a, ,b""#).is_err());
+        assert_eq!(read::<Strict>(r#""a,b""#).unwrap(), ["a", "b"]);
+    }
+
+    #[test]
+    fn separators_in_items() {
+        assert!(write::<Comma>(&["a,b", "c"]).is_err());
+        assert!(write::<Space>(&["a b"]).is_err());
+
+        let items = ["a,b", r"c\d", "", "e"];
+        let json = write::<Escaping>(&items).unwrap();
+        assert_eq!(json, r#""a\\,b,c\\\\d,,e""#);
+        assert_eq!(read::<Escaping>(&json).unwrap(), ["a,b", r"c\d", "e"]);
+        // A trailing escape character escapes nothing.
+        assert_eq!(read::<Escaping>(r#""a,b\\""#).unwrap(), ["a", "b"]);
+    }
+}
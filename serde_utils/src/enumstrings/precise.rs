@@ -0,0 +1,329 @@
+use std::{fmt, marker::PhantomData, str::FromStr};
+
+use serde::de::{Error, MapAccess, Unexpected, Visitor};
+
+// The key serde_json's `arbitrary_precision` feature wraps numbers in, so
+// they reach the visitor as their original digits.
+const JSON_NUMBER_TOKEN: &str = "$serde_json::private::Number";
+
+// A number type whose values are read without going through a lossy `f64`.
+pub trait Precise: FromStr + fmt::Display + Sized {
+    const EXPECTING: &'static str;
+
+    fn from_i128(value: i128) -> Option<Self>;
+
+    fn from_u128(value: u128) -> Option<Self>;
+
+    // Fails where `from_str` would round.
+    fn from_str_exact(s: &str) -> Result<Self, Self::Err> {
+        s.parse()
+    }
+}
+
+macro_rules! impl_precise_int {
+    ($type:ty, $expecting:literal) => {
+        impl Precise for $type {
+            const EXPECTING: &'static str = $expecting;
+
+            fn from_i128(value: i128) -> Option<Self> {
+                value.try_into().ok()
+            }
+
+            fn from_u128(value: u128) -> Option<Self> {
+                value.try_into().ok()
+            }
+        }
+    };
+}
+
+impl_precise_int!(u128, "an unsigned 128-bit integer as a number or a string");
+impl_precise_int!(i128, "a signed 128-bit integer as a number or a string");
+
+#[cfg(feature = "decimal")]
+impl Precise for rust_decimal::Decimal {
+    const EXPECTING: &'static str = "a decimal number as a number or a string";
+
+    fn from_i128(value: i128) -> Option<Self> {
+        Self::try_from_i128_with_scale(value, 0).ok()
+    }
+
+    fn from_u128(value: u128) -> Option<Self> {
+        i128::try_from(value).ok().and_then(Self::from_i128)
+    }
+
+    fn from_str_exact(s: &str) -> Result<Self, Self::Err> {
+        Self::from_str_exact(s)
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl Precise for num_bigint::BigInt {
+    const EXPECTING: &'static str = "an integer as a number or a string";
+
+    fn from_i128(value: i128) -> Option<Self> {
+        Some(value.into())
+    }
+
+    fn from_u128(value: u128) -> Option<Self> {
+        Some(value.into())
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl Precise for num_bigint::BigUint {
+    const EXPECTING: &'static str = "an unsigned integer as a number or a string";
+
+    fn from_i128(value: i128) -> Option<Self> {
+        u128::try_from(value).ok().map(Self::from)
+    }
+
+    fn from_u128(value: u128) -> Option<Self> {
+        Some(value.into())
+    }
+}
+
+// The shortest representation of `value`, when it can be taken for the
+// digits that were sent. See `jsontime::float_is_short`.
+fn exact_float(value: f64) -> Option<String> {
+    if !value.is_finite() {
+        return None;
+    }
+    let s = value.to_string();
+    crate::jsontime::float_is_short(value, &s).then_some(s)
+}
+
+struct PreciseVisitor<T>(PhantomData<fn() -> T>);
+
+impl<T> PreciseVisitor<T> {
+    const fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<'de, T> Visitor<'de> for PreciseVisitor<T>
+where
+    T: Precise,
+    T::Err: fmt::Display,
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(T::EXPECTING)
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        T::from_str_exact(v).map_err(|err| {
+            E::custom(format_args!(
+                "invalid value: string {:?}, expected {}: {}",
+                v,
+                T::EXPECTING,
+                err
+            ))
+        })
+    }
+
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> {
+        T::from_i128(v.into()).ok_or_else(|| E::invalid_value(Unexpected::Signed(v), &self))
+    }
+
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> {
+        T::from_u128(v.into()).ok_or_else(|| E::invalid_value(Unexpected::Unsigned(v), &self))
+    }
+
+    fn visit_i128<E: Error>(self, v: i128) -> Result<Self::Value, E> {
+        T::from_i128(v).ok_or_else(|| {
+            E::custom(format_args!(
+                "invalid value: integer `{}`, expected {}",
+                v,
+                T::EXPECTING
+            ))
+        })
+    }
+
+    fn visit_u128<E: Error>(self, v: u128) -> Result<Self::Value, E> {
+        T::from_u128(v).ok_or_else(|| {
+            E::custom(format_args!(
+                "invalid value: integer `{}`, expected {}",
+                v,
+                T::EXPECTING
+            ))
+        })
+    }
+
+    fn visit_f64<E: Error>(self, v: f64) -> Result<Self::Value, E> {
+        let Some(s) = exact_float(v) else {
+            return Err(E::custom(format_args!(
+                "floating point `{}` may have lost precision, expected {}",
+                v,
+                T::EXPECTING
+            )));
+        };
+        T::from_str_exact(&s).map_err(|_| E::invalid_value(Unexpected::Float(v), &self))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        match map.next_key::<String>()? {
+            Some(key) if key == JSON_NUMBER_TOKEN => {
+                let digits: String = map.next_value()?;
+                self.visit_str(&digits)
+            }
+            _ => Err(A::Error::invalid_type(Unexpected::Map, &self)),
+        }
+    }
+}
+
+// Strings and numbers. Floats with more digits than an `f64` keeps are
+// rejected, but a longer number that happened to round to a short float, as
+// 12.340000000000000001 does to 12.34, goes unnoticed: send such values as
+// strings, or enable serde_json's `arbitrary_precision` to get the digits
+// as they were sent.
+//
+// `serialize` writes a string; leave it off to use the value's own
+// `Serialize` instead.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Precise,
+    T::Err: fmt::Display,
+{
+    deserializer.deserialize_any(PreciseVisitor::new())
+}
+
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    T: fmt::Display,
+{
+    serializer.collect_str(value)
+}
+
+struct PreciseValue<T>(T);
+
+impl<'de, T> serde::Deserialize<'de> for PreciseValue<T>
+where
+    T: Precise,
+    T::Err: fmt::Display,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserialize(deserializer).map(Self)
+    }
+}
+
+pub mod option {
+    use std::fmt;
+
+    use serde::Deserialize;
+
+    use super::{Precise, PreciseValue};
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        T: Precise,
+        T::Err: fmt::Display,
+    {
+        let value = Option::<PreciseValue<T>>::deserialize(deserializer)?;
+        Ok(value.map(|value| value.0))
+    }
+
+    pub fn serialize<T, S>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+        T: fmt::Display,
+    {
+        crate::enumstrings::string_parse::option::serialize(value, serializer)
+    }
+}
+
+pub mod vec {
+    use std::fmt;
+
+    use serde::Deserialize;
+
+    use super::{Precise, PreciseValue};
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        T: Precise,
+        T::Err: fmt::Display,
+    {
+        let values = Vec::<PreciseValue<T>>::deserialize(deserializer)?;
+        Ok(values.into_iter().map(|value| value.0).collect())
+    }
+
+    pub fn serialize<T, S>(values: &[T], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+        T: fmt::Display,
+    {
+        crate::enumstrings::string_parse::vec::serialize(values, serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::de::value::{Error as ValueError, F64Deserializer, StrDeserializer};
+    use serde::de::IntoDeserializer;
+
+    use super::*;
+
+    fn from_str<T: Precise>(s: &str) -> Result<T, ValueError>
+    where
+        T::Err: fmt::Display,
+    {
+        let deserializer: StrDeserializer<ValueError> = s.into_deserializer();
+        deserialize(deserializer)
+    }
+
+    fn from_f64<T: Precise>(v: f64) -> Result<T, ValueError>
+    where
+        T::Err: fmt::Display,
+    {
+        let deserializer: F64Deserializer<ValueError> = v.into_deserializer();
+        deserialize(deserializer)
+    }
+
+    #[test]
+    fn integers() {
+        assert_eq!(
+            from_str::<u128>("340282366920938463463374607431768211455"),
+            Ok(u128::MAX)
+        );
+        assert_eq!(
+            from_str::<i128>("-170141183460469231731687303715884105728"),
+            Ok(i128::MIN)
+        );
+        assert!(from_str::<u128>("340282366920938463463374607431768211456").is_err());
+        assert!(from_str::<u128>("-1").is_err());
+        assert!(from_str::<u128>("").is_err());
+        assert_eq!(from_f64::<u128>(9_007_199_254_740_992.0), Ok(1 << 53));
+        assert!(from_f64::<u128>(18_014_398_509_481_984.0).is_err());
+        assert!(from_f64::<i128>(1.5).is_err());
+        assert!(from_f64::<i128>(f64::NAN).is_err());
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn decimals() {
+        use rust_decimal::Decimal;
+
+        let decimal = |s: &str| s.parse::<Decimal>().unwrap();
+        assert_eq!(from_f64::<Decimal>(12.5), Ok(decimal("12.5")));
+        assert_eq!(from_f64::<Decimal>(-0.1), Ok(decimal("-0.1")));
+        assert!(from_f64::<Decimal>(0.1 + 0.2).is_err());
+        assert_eq!(
+            from_str::<Decimal>("12.340000000000000001"),
+            Ok(decimal("12.340000000000000001"))
+        );
+        // More places than a `Decimal` holds.
+        assert!(from_str::<Decimal>("0.000000000000000000000000000012345").is_err());
+        assert!(from_str::<Decimal>("79228162514264337593543950336").is_err());
+    }
+}
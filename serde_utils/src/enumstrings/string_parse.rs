@@ -0,0 +1,633 @@
+use std::{fmt, marker::PhantomData, str::FromStr};
+
+use serde::de::{Error, Expected, IntoDeserializer, Unexpected, Visitor};
+
+// What a string-or-number field accepts besides plain numbers of its own type.
+// Implement it on a marker type to pick a different combination:
+//
+//     enum Loose {}
+//
+//     impl ParseOptions for Loose {
+//         const TRIM: bool = true;
+//         const HEX: bool = true;
+//     }
+pub trait ParseOptions {
+    // Numeric strings such as "123".
+    const ALLOW_STRINGS: bool = true;
+    // Whole floats (3.0, "3.0", 1e3) for integer types.
+    const ALLOW_FLOATS: bool = false;
+    // Surrounding whitespace in strings.
+    const TRIM: bool = false;
+    // "0x1f" and "-0x1F" for integer types.
+    const HEX: bool = false;
+    // "" as a missing value in `Option` fields; an error otherwise.
+    const EMPTY_AS_NONE: bool = false;
+}
+
+// Numbers and numeric strings, exactly as written.
+#[derive(Clone, Copy, Debug)]
+pub enum Standard {}
+
+impl ParseOptions for Standard {}
+
+// Numbers only.
+#[derive(Clone, Copy, Debug)]
+pub enum NumbersOnly {}
+
+impl ParseOptions for NumbersOnly {
+    const ALLOW_STRINGS: bool = false;
+}
+
+// Everything the options allow.
+#[derive(Clone, Copy, Debug)]
+pub enum Lenient {}
+
+impl ParseOptions for Lenient {
+    const ALLOW_FLOATS: bool = true;
+    const TRIM: bool = true;
+    const HEX: bool = true;
+    const EMPTY_AS_NONE: bool = true;
+}
+
+// Reads a `T` from a number of its own type or, through `FromStr`, from a
+// string. Numbers are handed to `T`'s own `Deserialize`, so range checks and
+// their messages are the usual ones. `None` stands for an accepted empty
+// string or a null.
+pub(crate) struct StringOrNumberVisitor<T, O> {
+    marker: PhantomData<fn() -> (T, O)>,
+}
+
+impl<T, O> StringOrNumberVisitor<T, O> {
+    pub(crate) const fn new() -> Self {
+        Self {
+            marker: PhantomData,
+        }
+    }
+}
+
+fn forward<'de, T, E, V>(value: V) -> Result<T, E>
+where
+    T: serde::Deserialize<'de>,
+    E: Error,
+    V: IntoDeserializer<'de, E>,
+{
+    T::deserialize(value.into_deserializer())
+}
+
+// `value` when it is a whole number that fits an `i64` or `u64`.
+fn whole_float<'de, T, E>(value: f64) -> Option<Result<T, E>>
+where
+    T: serde::Deserialize<'de>,
+    E: Error,
+{
+    if value.fract() != 0.0 || !value.is_finite() {
+        return None;
+    }
+    // 2^64 and -2^63, as `u64::MAX as f64` rounds up to 2^64 itself.
+    const U64_END: f64 = 18_446_744_073_709_551_616.0;
+    const I64_START: f64 = -9_223_372_036_854_775_808.0;
+    if (0.0..U64_END).contains(&value) {
+        Some(forward(value as u64))
+    } else if (I64_START..0.0).contains(&value) {
+        Some(forward(value as i64))
+    } else {
+        None
+    }
+}
+
+fn parse_hex<'de, T, E>(s: &str) -> Option<Result<T, E>>
+where
+    T: serde::Deserialize<'de>,
+    E: Error,
+{
+    let (negative, unsigned) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+    let digits = unsigned
+        .strip_prefix("0x")
+        .or_else(|| unsigned.strip_prefix("0X"))?;
+    // `from_str_radix` would also take a sign here.
+    if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let magnitude = u128::from_str_radix(digits, 16).ok()?;
+    Some(if negative {
+        match 0i128.checked_sub_unsigned(magnitude) {
+            Some(value) => match i64::try_from(value) {
+                Ok(value) => forward(value),
+                Err(_) => forward(value),
+            },
+            None => Err(E::custom(format_args!("{:?} is out of range", s))),
+        }
+    } else {
+        match u64::try_from(magnitude) {
+            Ok(value) => forward(value),
+            Err(_) => forward(magnitude),
+        }
+    })
+}
+
+impl<'de, T, O> StringOrNumberVisitor<T, O>
+where
+    T: FromStr + serde::Deserialize<'de>,
+    T::Err: fmt::Display,
+    O: ParseOptions,
+{
+    fn parse<E: Error>(&self, v: &str) -> Result<Option<T>, E> {
+        if !O::ALLOW_STRINGS {
+            return Err(E::invalid_type(Unexpected::Str(v), self));
+        }
+        let s = if O::TRIM { v.trim() } else { v };
+        if s.is_empty() {
+            return if O::EMPTY_AS_NONE {
+                Ok(None)
+            } else {
+                Err(E::invalid_value(Unexpected::Str(v), self))
+            };
+        }
+
+        let err = match T::from_str(s) {
+            Ok(value) => return Ok(Some(value)),
+            Err(err) => err,
+        };
+        if O::HEX {
+            if let Some(result) = parse_hex(s) {
+                return result.map(Some);
+            }
+        }
+        if O::ALLOW_FLOATS {
+            if let Some(result) = s.parse::<f64>().ok().and_then(whole_float) {
+                return result.map(Some);
+            }
+        }
+        Err(E::custom(format_args!(
+            "invalid value: string {:?}, expected {}: {}",
+            v, self as &dyn Expected, err
+        )))
+    }
+}
+
+impl<'de, T, O> Visitor<'de> for StringOrNumberVisitor<T, O>
+where
+    T: FromStr + serde::Deserialize<'de>,
+    T::Err: fmt::Display,
+    O: ParseOptions,
+{
+    type Value = Option<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if O::ALLOW_STRINGS {
+            f.write_str("a number or a numeric string")
+        } else {
+            f.write_str("a number")
+        }
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        self.parse(v)
+    }
+
+    fn visit_bool<E: Error>(self, v: bool) -> Result<Self::Value, E> {
+        forward(v).map(Some)
+    }
+
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> {
+        forward(v).map(Some)
+    }
+
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> {
+        forward(v).map(Some)
+    }
+
+    fn visit_i128<E: Error>(self, v: i128) -> Result<Self::Value, E> {
+        forward(v).map(Some)
+    }
+
+    fn visit_u128<E: Error>(self, v: u128) -> Result<Self::Value, E> {
+        forward(v).map(Some)
+    }
+
+    fn visit_f64<E: Error>(self, v: f64) -> Result<Self::Value, E> {
+        match forward::<T, E, _>(v) {
+            Ok(value) => Ok(Some(value)),
+            Err(err) if O::ALLOW_FLOATS => match whole_float(v) {
+                Some(result) => result.map(Some),
+                None => Err(err),
+            },
+            Err(err) => Err(err),
+        }
+    }
+
+    fn visit_none<E: Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_unit<E: Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+}
+
+pub fn deserialize_with<'de, O, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    O: ParseOptions,
+    D: serde::Deserializer<'de>,
+    T: FromStr + serde::Deserialize<'de>,
+    T::Err: fmt::Display,
+{
+    match deserializer.deserialize_any(StringOrNumberVisitor::<T, O>::new())? {
+        Some(value) => Ok(value),
+        None => Err(D::Error::custom(format_args!(
+            "missing value, expected {}",
+            &StringOrNumberVisitor::<T, O>::new() as &dyn Expected
+        ))),
+    }
+}
+
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: FromStr + serde::Deserialize<'de>,
+    T::Err: fmt::Display,
+{
+    deserialize_with::<Standard, T, D>(deserializer)
+}
+
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    T: fmt::Display,
+{
+    serializer.collect_str(value)
+}
+
+// A single string-or-number inside a container.
+struct Parsed<T, O>(T, PhantomData<fn() -> O>);
+
+impl<'de, T, O> serde::Deserialize<'de> for Parsed<T, O>
+where
+    T: FromStr + serde::Deserialize<'de>,
+    T::Err: fmt::Display,
+    O: ParseOptions,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserialize_with::<O, T, D>(deserializer).map(|value| Self(value, PhantomData))
+    }
+}
+
+// Serializes a value inside a container through `Display`.
+struct Shown<'a, T>(&'a T);
+
+impl<T: fmt::Display> serde::Serialize for Shown<'_, T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self.0)
+    }
+}
+
+// `Option<T>` as a number, a numeric string or null. With `EMPTY_AS_NONE`,
+// "" reads as `None` too.
+pub mod option {
+    use std::{fmt, str::FromStr};
+
+    use super::{ParseOptions, Standard, StringOrNumberVisitor};
+
+    pub fn deserialize_with<'de, O, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        O: ParseOptions,
+        D: serde::Deserializer<'de>,
+        T: FromStr + serde::Deserialize<'de>,
+        T::Err: fmt::Display,
+    {
+        deserializer.deserialize_any(StringOrNumberVisitor::<T, O>::new())
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        T: FromStr + serde::Deserialize<'de>,
+        T::Err: fmt::Display,
+    {
+        deserialize_with::<Standard, T, D>(deserializer)
+    }
+
+    pub fn serialize<T, S>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+        T: fmt::Display,
+    {
+        match value {
+            Some(value) => serializer.collect_str(value),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+// `Vec<T>` as a sequence of numbers or numeric strings.
+pub mod vec {
+    use std::{fmt, str::FromStr};
+
+    use serde::Deserialize;
+
+    use super::{ParseOptions, Parsed, Shown, Standard};
+
+    pub fn deserialize_with<'de, O, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        O: ParseOptions,
+        D: serde::Deserializer<'de>,
+        T: FromStr + serde::Deserialize<'de>,
+        T::Err: fmt::Display,
+    {
+        let values = Vec::<Parsed<T, O>>::deserialize(deserializer)?;
+        Ok(values.into_iter().map(|value| value.0).collect())
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        T: FromStr + serde::Deserialize<'de>,
+        T::Err: fmt::Display,
+    {
+        deserialize_with::<Standard, T, D>(deserializer)
+    }
+
+    pub fn serialize<T, S>(values: &[T], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+        T: fmt::Display,
+    {
+        serializer.collect_seq(values.iter().map(Shown))
+    }
+}
+
+// `HashMap<K, V>` whose values are numbers or numeric strings.
+pub mod hashmap_values {
+    use std::{
+        collections::HashMap,
+        fmt,
+        hash::{BuildHasher, Hash},
+        marker::PhantomData,
+        str::FromStr,
+    };
+
+    use serde::de::{MapAccess, Visitor};
+
+    use super::{ParseOptions, Parsed, Shown, Standard};
+
+    struct MapVisitor<M, O>(PhantomData<fn() -> (M, O)>);
+
+    impl<'de, K, V, H, O> Visitor<'de> for MapVisitor<HashMap<K, V, H>, O>
+    where
+        K: serde::Deserialize<'de> + Eq + Hash,
+        V: FromStr + serde::Deserialize<'de>,
+        V::Err: fmt::Display,
+        H: BuildHasher + Default,
+        O: ParseOptions,
+    {
+        type Value = HashMap<K, V, H>;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a map of numbers or numeric strings")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let mut values = HashMap::with_capacity_and_hasher(
+                map.size_hint().unwrap_or(0).min(4096),
+                H::default(),
+            );
+            while let Some((key, value)) = map.next_entry::<K, Parsed<V, O>>()? {
+                values.insert(key, value.0);
+            }
+            Ok(values)
+        }
+    }
+
+    pub fn deserialize_with<'de, O, K, V, H, D>(
+        deserializer: D,
+    ) -> Result<HashMap<K, V, H>, D::Error>
+    where
+        O: ParseOptions,
+        D: serde::Deserializer<'de>,
+        K: serde::Deserialize<'de> + Eq + Hash,
+        V: FromStr + serde::Deserialize<'de>,
+        V::Err: fmt::Display,
+        H: BuildHasher + Default,
+    {
+        deserializer.deserialize_map(MapVisitor::<HashMap<K, V, H>, O>(PhantomData))
+    }
+
+    pub fn deserialize<'de, K, V, H, D>(deserializer: D) -> Result<HashMap<K, V, H>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        K: serde::Deserialize<'de> + Eq + Hash,
+        V: FromStr + serde::Deserialize<'de>,
+        V::Err: fmt::Display,
+        H: BuildHasher + Default,
+    {
+        deserialize_with::<Standard, K, V, H, D>(deserializer)
+    }
+
+    pub fn serialize<K, V, H, S>(
+        values: &HashMap<K, V, H>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+        K: serde::Serialize,
+        V: fmt::Display,
+    {
+        serializer.collect_map(values.iter().map(|(key, value)| (key, Shown(value))))
+    }
+}
+
+// `BTreeMap<K, V>` keyed by numbers or numeric strings, such as the string
+// keys of a JSON object.
+pub mod btreemap_keys {
+    use std::{collections::BTreeMap, fmt, marker::PhantomData, str::FromStr};
+
+    use serde::de::{MapAccess, Visitor};
+
+    use super::{ParseOptions, Parsed, Shown, Standard};
+
+    struct MapVisitor<M, O>(PhantomData<fn() -> (M, O)>);
+
+    impl<'de, K, V, O> Visitor<'de> for MapVisitor<BTreeMap<K, V>, O>
+    where
+        K: FromStr + serde::Deserialize<'de> + Ord,
+        K::Err: fmt::Display,
+        V: serde::Deserialize<'de>,
+        O: ParseOptions,
+    {
+        type Value = BTreeMap<K, V>;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a map keyed by numbers or numeric strings")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let mut values = BTreeMap::new();
+            while let Some((key, value)) = map.next_entry::<Parsed<K, O>, V>()? {
+                values.insert(key.0, value);
+            }
+            Ok(values)
+        }
+    }
+
+    pub fn deserialize_with<'de, O, K, V, D>(deserializer: D) -> Result<BTreeMap<K, V>, D::Error>
+    where
+        O: ParseOptions,
+        D: serde::Deserializer<'de>,
+        K: FromStr + serde::Deserialize<'de> + Ord,
+        K::Err: fmt::Display,
+        V: serde::Deserialize<'de>,
+    {
+        deserializer.deserialize_map(MapVisitor::<BTreeMap<K, V>, O>(PhantomData))
+    }
+
+    pub fn deserialize<'de, K, V, D>(deserializer: D) -> Result<BTreeMap<K, V>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        K: FromStr + serde::Deserialize<'de> + Ord,
+        K::Err: fmt::Display,
+        V: serde::Deserialize<'de>,
+    {
+        deserialize_with::<Standard, K, V, D>(deserializer)
+    }
+
+    pub fn serialize<K, V, S>(values: &BTreeMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+        K: fmt::Display,
+        V: serde::Serialize,
+    {
+        serializer.collect_map(values.iter().map(|(key, value)| (Shown(key), value)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read<O: ParseOptions, T>(json: &str) -> Result<T, serde_json::Error>
+    where
+        T: FromStr + for<'de> serde::Deserialize<'de>,
+        T::Err: fmt::Display,
+    {
+        deserialize_with::<O, T, _>(&mut serde_json::Deserializer::from_str(json))
+    }
+
+    fn read_option<O: ParseOptions, T>(json: &str) -> Result<Option<T>, serde_json::Error>
+    where
+        T: FromStr + for<'de> serde::Deserialize<'de>,
+        T::Err: fmt::Display,
+    {
+        option::deserialize_with::<O, T, _>(&mut serde_json::Deserializer::from_str(json))
+    }
+
+    #[test]
+    fn standard() {
+        assert_eq!(read::<Standard, u8>("255").unwrap(), 255);
+        assert_eq!(read::<Standard, u8>(r#""255""#).unwrap(), 255);
+        assert_eq!(read::<Standard, i32>(r#""-7""#).unwrap(), -7);
+        assert_eq!(read::<Standard, f64>(r#""1.5""#).unwrap(), 1.5);
+        assert!(read::<Standard, u8>("256").is_err());
+        assert!(read::<Standard, u8>(r#""256""#).is_err());
+        assert!(read::<Standard, u8>(r#""-1""#).is_err());
+        assert!(read::<Standard, u32>(r#"" 1""#).is_err());
+        assert!(read::<Standard, u32>(r#""""#).is_err());
+        assert!(read::<Standard, u32>("3.0").is_err());
+        assert!(read::<Standard, u32>(r#""0x1f""#).is_err());
+        assert!(read::<Standard, u32>("null").is_err());
+
+        let err = read::<Standard, u32>(r#""12abc""#).unwrap_err().to_string();
+        assert!(err.contains(r#""12abc""#), "{}", err);
+        assert_eq!(read_option::<Standard, u32>("null").unwrap(), None);
+        assert!(read_option::<Standard, u32>(r#""""#).is_err());
+    }
+
+    #[test]
+    fn numbers_only() {
+        assert_eq!(read::<NumbersOnly, i64>("-3").unwrap(), -3);
+        assert!(read::<NumbersOnly, i64>(r#""-3""#).is_err());
+    }
+
+    #[test]
+    fn trimming_and_empty_strings() {
+        assert_eq!(read::<Lenient, u32>(r#"" 42\n""#).unwrap(), 42);
+        assert_eq!(read_option::<Lenient, u32>(r#""""#).unwrap(), None);
+        assert_eq!(read_option::<Lenient, u32>(r#""  ""#).unwrap(), None);
+        assert_eq!(read_option::<Lenient, u32>(r#""7""#).unwrap(), Some(7));
+        assert!(read::<Lenient, u32>(r#""""#).is_err());
+    }
+
+    #[test]
+    fn hex() {
+        assert_eq!(read::<Lenient, u32>(r#""0x1f""#).unwrap(), 31);
+        assert_eq!(read::<Lenient, u32>(r#""0X1F""#).unwrap(), 31);
+        assert_eq!(read::<Lenient, u32>(r#""+0x10""#).unwrap(), 16);
+        assert_eq!(read::<Lenient, i32>(r#""-0x1F""#).unwrap(), -31);
+        assert_eq!(read::<Lenient, i8>(r#""-0x80""#).unwrap(), i8::MIN);
+        assert!(read::<Lenient, i8>(r#""0x80""#).is_err());
+        assert!(read::<Lenient, u8>(r#""-0x1""#).is_err());
+        assert_eq!(
+            read::<Lenient, i64>(r#""-0x8000000000000000""#).unwrap(),
+            i64::MIN
+        );
+        assert_eq!(
+            read::<Lenient, u128>(r#""0x10000000000000000""#).unwrap(),
+            1 << 64
+        );
+        assert!(read::<Lenient, u64>(r#""0x10000000000000000""#).is_err());
+        assert!(read::<Lenient, i128>(r#""-0xffffffffffffffffffffffffffffffff""#).is_err());
+        for bad in ["0x", "-0x", "0x+1", "0x-1", "--0x1", "0x1g", "x1"] {
+            assert!(
+                read::<Lenient, i64>(&format!("{:?}", bad)).is_err(),
+                "{}",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn whole_floats() {
+        assert_eq!(read::<Lenient, u32>("3.0").unwrap(), 3);
+        assert_eq!(read::<Lenient, u32>(r#""3.0""#).unwrap(), 3);
+        assert_eq!(read::<Lenient, u32>("1e3").unwrap(), 1000);
+        assert_eq!(read::<Lenient, i32>("-2.0").unwrap(), -2);
+        assert_eq!(read::<Lenient, u32>("-0.0").unwrap(), 0);
+        assert!(read::<Lenient, u32>("3.5").is_err());
+        assert!(read::<Lenient, u32>(r#""NaN""#).is_err());
+        assert!(read::<Lenient, u32>(r#""inf""#).is_err());
+        assert!(read::<Lenient, u8>("256.0").is_err());
+
+        // 2^64 is past `u64`, -2^63 is the first `i64`.
+        assert_eq!(
+            read::<Lenient, u64>("18446744073709549568.0").unwrap(),
+            18_446_744_073_709_549_568
+        );
+        assert!(read::<Lenient, u64>("18446744073709551616.0").is_err());
+        assert_eq!(
+            read::<Lenient, i64>("-9223372036854775808.0").unwrap(),
+            i64::MIN
+        );
+        assert!(read::<Lenient, i64>("-9223372036854777856.0").is_err());
+    }
+}
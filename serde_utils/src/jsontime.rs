@@ -1,107 +1,1042 @@
+use std::{fmt, marker::PhantomData};
+
+use serde::de::{Error, Unexpected, Visitor};
 use time::OffsetDateTime;
 
-#[derive(Clone, Debug)]
-pub struct UnixMilliString(pub OffsetDateTime);
+mod civil;
+mod convert;
+mod detect;
+pub mod duration;
+mod format;
+mod offset;
+#[cfg(feature = "tz")]
+mod zoned;
 
-impl<'de> serde::Deserialize<'de> for UnixMilliString {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+pub use civil::*;
+pub use convert::*;
+pub use detect::*;
+pub use format::*;
+pub use offset::*;
+#[cfg(feature = "tz")]
+pub use zoned::*;
+
+pub trait Unit {
+    const NANOS: i128;
+    const NAME: &'static str;
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Seconds {}
+
+impl Unit for Seconds {
+    const NANOS: i128 = 1_000_000_000;
+    const NAME: &'static str = "seconds";
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Millis {}
+
+impl Unit for Millis {
+    const NANOS: i128 = 1_000_000;
+    const NAME: &'static str = "milliseconds";
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Micros {}
+
+impl Unit for Micros {
+    const NANOS: i128 = 1_000;
+    const NAME: &'static str = "microseconds";
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Nanos {}
+
+impl Unit for Nanos {
+    const NANOS: i128 = 1;
+    const NAME: &'static str = "nanoseconds";
+}
+
+// How nanoseconds are brought down to whole units on output, and how digits
+// finer than a nanosecond are handled on input.
+pub trait Rounding {
+    // Whether floats written with this mode must read back exactly.
+    const EXACT: bool = false;
+
+    // `value / divisor` for a positive `divisor`, or `None` when the mode
+    // does not accept the precision that would be lost.
+    fn div(value: i128, divisor: i128) -> Option<i128>;
+}
+
+// Toward zero, so -1.5 units become -1.
+#[derive(Clone, Copy, Debug)]
+pub enum Truncate {}
+
+impl Rounding for Truncate {
+    fn div(value: i128, divisor: i128) -> Option<i128> {
+        Some(value / divisor)
+    }
+}
+
+// Toward negative infinity, so -1.5 units become -2 and every date-time maps
+// to the unit it falls in.
+#[derive(Clone, Copy, Debug)]
+pub enum Floor {}
+
+impl Rounding for Floor {
+    fn div(value: i128, divisor: i128) -> Option<i128> {
+        Some(value.div_euclid(divisor))
+    }
+}
+
+// To the nearest unit, halves away from zero.
+#[derive(Clone, Copy, Debug)]
+pub enum Round {}
+
+impl Rounding for Round {
+    fn div(value: i128, divisor: i128) -> Option<i128> {
+        let quotient = value / divisor;
+        if (value % divisor).unsigned_abs() * 2 >= divisor.unsigned_abs() {
+            Some(quotient + value.signum())
+        } else {
+            Some(quotient)
+        }
+    }
+}
+
+// Fail instead of dropping anything.
+#[derive(Clone, Copy, Debug)]
+pub enum RejectLossy {}
+
+impl Rounding for RejectLossy {
+    const EXACT: bool = true;
+
+    fn div(value: i128, divisor: i128) -> Option<i128> {
+        (value % divisor == 0).then(|| value / divisor)
+    }
+}
+
+fn nanos_to_units<U: Unit, M: Rounding, E: serde::ser::Error>(unix_nanos: i128) -> Result<i128, E> {
+    M::div(unix_nanos, U::NANOS).ok_or_else(|| {
+        E::custom(format_args!(
+            "{} nanoseconds is not a whole number of {}",
+            unix_nanos,
+            U::NAME
+        ))
+    })
+}
+
+// The wire representation of the epoch value: which forms are accepted when
+// deserializing and which one is produced when serializing. Numeric strings
+// may have a fractional part. `Either` accepts everything.
+pub trait Repr {
+    const ACCEPTS_STR: bool;
+    const ACCEPTS_INT: bool;
+    const ACCEPTS_FLOAT: bool;
+    const EXPECTING: &'static str;
+
+    fn serialize<U: Unit, M: Rounding, S: serde::Serializer>(
+        unix_nanos: i128,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>;
+
+    // Asks for the form the value is written in, which formats that are not
+    // self-describing rely on.
+    fn deserialize<'de, U, D, V>(deserializer: D, visitor: V) -> Result<V::Value, D::Error>
     where
+        U: Unit,
         D: serde::Deserializer<'de>,
-    {
-        let s = String::deserialize(deserializer)?;
-        let unix_milli: i128 = s.parse().map_err(serde::de::Error::custom)?;
-        let unix_nano = unix_milli * 1_000_000;
-        Ok(Self(
-            OffsetDateTime::from_unix_timestamp_nanos(unix_nano)
-                .map_err(serde::de::Error::custom)?,
-        ))
-    }
+        V: Visitor<'de>;
 }
 
-impl serde::Serialize for UnixMilliString {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+// Nanosecond counts past 2262 don't fit an i64, so they are always written
+// and read as i128.
+const fn wide_int<U: Unit>() -> bool {
+    U::NANOS < 1_000
+}
+
+// "1700000000"
+#[derive(Clone, Copy, Debug)]
+pub enum Str {}
+
+impl Repr for Str {
+    const ACCEPTS_STR: bool = true;
+    const ACCEPTS_INT: bool = false;
+    const ACCEPTS_FLOAT: bool = false;
+    const EXPECTING: &'static str = "a numeric string";
+
+    fn serialize<U: Unit, M: Rounding, S: serde::Serializer>(
+        unix_nanos: i128,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&nanos_to_units::<U, M, S::Error>(unix_nanos)?)
+    }
+
+    fn deserialize<'de, U, D, V>(deserializer: D, visitor: V) -> Result<V::Value, D::Error>
     where
-        S: serde::Serializer,
+        U: Unit,
+        D: serde::Deserializer<'de>,
+        V: Visitor<'de>,
     {
-        let unix_milli = (self.0.unix_timestamp_nanos() / 1_000_000) as i64;
-        serializer.serialize_str(&unix_milli.to_string())
+        deserializer.deserialize_str(visitor)
     }
 }
 
-#[derive(Clone, Debug)]
-pub struct UnixMicroString(pub OffsetDateTime);
+// 1700000000
+#[derive(Clone, Copy, Debug)]
+pub enum Int {}
 
-impl<'de> serde::Deserialize<'de> for UnixMicroString {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+impl Repr for Int {
+    const ACCEPTS_STR: bool = false;
+    const ACCEPTS_INT: bool = true;
+    const ACCEPTS_FLOAT: bool = false;
+    const EXPECTING: &'static str = "an integer";
+
+    fn serialize<U: Unit, M: Rounding, S: serde::Serializer>(
+        unix_nanos: i128,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        use serde::ser::Error;
+
+        let value = nanos_to_units::<U, M, S::Error>(unix_nanos)?;
+        if wide_int::<U>() {
+            return serializer.serialize_i128(value);
+        }
+        let value = i64::try_from(value)
+            .map_err(|_| S::Error::custom(format_args!("{} {} is out of range", value, U::NAME)))?;
+        serializer.serialize_i64(value)
+    }
+
+    fn deserialize<'de, U, D, V>(deserializer: D, visitor: V) -> Result<V::Value, D::Error>
     where
+        U: Unit,
         D: serde::Deserializer<'de>,
+        V: Visitor<'de>,
     {
-        let s = String::deserialize(deserializer)?;
-        let unix_micro: i128 = s.parse().map_err(serde::de::Error::custom)?;
-        Ok(Self(
-            OffsetDateTime::from_unix_timestamp_nanos(unix_micro * 1000)
-                .map_err(serde::de::Error::custom)?,
-        ))
+        if wide_int::<U>() {
+            deserializer.deserialize_i128(visitor)
+        } else {
+            deserializer.deserialize_i64(visitor)
+        }
     }
 }
 
-impl serde::Serialize for UnixMicroString {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+// 1700000000.123
+//
+// Written as the closest `f64` that is no further from zero than the value.
+// With `RejectLossy`, serializing fails unless that float reads back as the
+// same nanosecond, and deserializing rejects floats with more significant
+// digits than an `f64` keeps.
+#[derive(Clone, Copy, Debug)]
+pub enum Float {}
+
+impl Repr for Float {
+    const ACCEPTS_STR: bool = false;
+    const ACCEPTS_INT: bool = true;
+    const ACCEPTS_FLOAT: bool = true;
+    const EXPECTING: &'static str = "a number";
+
+    fn serialize<U: Unit, M: Rounding, S: serde::Serializer>(
+        unix_nanos: i128,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        use serde::ser::Error;
+
+        let units = unix_nanos.div_euclid(U::NANOS) as f64;
+        let fraction = unix_nanos.rem_euclid(U::NANOS) as f64 / U::NANOS as f64;
+        let mut value = units + fraction;
+        // The nearest f64 can lie past either end of the date-time range.
+        if float_units_to_nanos::<U, Truncate>(value)
+            .is_ok_and(|nanos| nanos.unsigned_abs() > unix_nanos.unsigned_abs())
+        {
+            value = if value > 0.0 {
+                value.next_down()
+            } else {
+                value.next_up()
+            };
+        }
+        if M::EXACT && float_units_to_nanos::<U, RejectLossy>(value) != Ok(unix_nanos) {
+            return Err(S::Error::custom(format_args!(
+                "{} nanoseconds cannot be written exactly as a float number of {}",
+                unix_nanos,
+                U::NAME
+            )));
+        }
+        serializer.serialize_f64(value)
+    }
+
+    fn deserialize<'de, U, D, V>(deserializer: D, visitor: V) -> Result<V::Value, D::Error>
     where
-        S: serde::Serializer,
+        U: Unit,
+        D: serde::Deserializer<'de>,
+        V: Visitor<'de>,
     {
-        let unix_micro: i128 = self.0.unix_timestamp_nanos() / 1000;
-        serializer.serialize_str(&unix_micro.to_string())
+        deserializer.deserialize_f64(visitor)
     }
 }
 
-#[derive(Clone, Debug)]
-pub struct UnixNanoString(pub OffsetDateTime);
+// Accepts integers, floats and numeric strings alike, for APIs that send
+// either, and serializes as a string. Needs a self-describing format.
+#[derive(Clone, Copy, Debug)]
+pub enum Either {}
 
-impl<'de> serde::Deserialize<'de> for UnixNanoString {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+impl Repr for Either {
+    const ACCEPTS_STR: bool = true;
+    const ACCEPTS_INT: bool = true;
+    const ACCEPTS_FLOAT: bool = true;
+    const EXPECTING: &'static str = "an integer, a float or a numeric string";
+
+    fn serialize<U: Unit, M: Rounding, S: serde::Serializer>(
+        unix_nanos: i128,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        Str::serialize::<U, M, S>(unix_nanos, serializer)
+    }
+
+    fn deserialize<'de, U, D, V>(deserializer: D, visitor: V) -> Result<V::Value, D::Error>
     where
+        U: Unit,
         D: serde::Deserializer<'de>,
+        V: Visitor<'de>,
     {
-        let s = String::deserialize(deserializer)?;
-        let unix_nano: i128 = s.parse().map_err(serde::de::Error::custom)?;
-        Ok(Self(
-            OffsetDateTime::from_unix_timestamp_nanos(unix_nano)
-                .map_err(serde::de::Error::custom)?,
-        ))
+        deserializer.deserialize_any(visitor)
     }
 }
 
-impl serde::Serialize for UnixNanoString {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
+type Markers<U, R, M> = PhantomData<fn() -> (U, R, M)>;
+
+// `M` picks what happens to precision finer than `U`, see `Rounding`. Unless
+// `R` says otherwise, numbers are accepted as well as numeric strings and
+// strings are written.
+pub struct UnixTimestamp<U, R = Either, T = OffsetDateTime, M = Truncate>(pub T, Markers<U, R, M>);
+
+pub type UnixString = UnixTimestamp<Seconds, Either>;
+pub type UnixMilliString = UnixTimestamp<Millis, Either>;
+pub type UnixMicroString = UnixTimestamp<Micros, Either>;
+pub type UnixNanoString = UnixTimestamp<Nanos, Either>;
+
+impl<U, R, T, M> UnixTimestamp<U, R, T, M> {
+    pub const fn new(datetime: T) -> Self {
+        Self(datetime, PhantomData)
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+fn units_to_nanos<U: Unit, E: Error>(units: i128) -> Result<i128, E> {
+    units
+        .checked_mul(U::NANOS)
+        .ok_or_else(|| E::custom(format_args!("{} {} is out of range", units, U::NAME)))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DecimalError {
+    Invalid,
+    OutOfRange,
+    Lossy,
+    Imprecise,
+}
+
+impl DecimalError {
+    fn into_de<U: Unit, E: Error>(
+        self,
+        value: impl fmt::Display,
+        exp: &dyn serde::de::Expected,
+    ) -> E {
+        match self {
+            Self::Invalid => E::custom(format_args!("invalid value: {}, expected {}", value, exp)),
+            Self::OutOfRange => E::custom(format_args!("{} {} is out of range", value, U::NAME)),
+            Self::Lossy => E::custom(format_args!(
+                "{} {} has digits finer than a nanosecond",
+                value,
+                U::NAME
+            )),
+            Self::Imprecise => E::custom(format_args!(
+                "floating point {} {} may have lost precision",
+                value,
+                U::NAME
+            )),
+        }
+    }
+}
+
+// An f64 tells apart every decimal number of up to 15 significant digits,
+// and every integer up to 2^53.
+const FLOAT_DIGITS: usize = 15;
+const FLOAT_EXACT_INT: f64 = 9_007_199_254_740_992.0;
+
+// Whether `s`, the shortest representation of `value`, can be taken for the
+// digits that were sent. Longer input that happened to round to a short
+// float cannot be told apart; send strings when that matters.
+pub(crate) fn float_is_short(value: f64, s: &str) -> bool {
+    if value.fract() == 0.0 {
+        return value.abs() <= FLOAT_EXACT_INT;
+    }
+    let digits = s.trim_start_matches('-').replace('.', "");
+    digits.trim_start_matches('0').trim_end_matches('0').len() <= FLOAT_DIGITS
+}
+
+// Goes through the shortest decimal representation of the float, so 1.1
+// seconds become exactly 1.1 seconds rather than 1.100000000000000088.
+// Modes that reject lossy input also reject floats that may be a longer
+// number rounded on the way, see `float_is_short`.
+fn float_units_to_nanos<U: Unit, M: Rounding>(units: f64) -> Result<i128, DecimalError> {
+    if !units.is_finite() {
+        return Err(DecimalError::OutOfRange);
+    }
+    let s = units.to_string();
+    if M::EXACT && !float_is_short(units, &s) {
+        return Err(DecimalError::Imprecise);
+    }
+    parse_decimal_nanos::<U, M>(&s)
+}
+
+// Parses "1700000000", "-12", "1700000000.123456" exactly, without going
+// through f64. Digits finer than a nanosecond are handled according to `M`.
+fn parse_decimal_nanos<U: Unit, M: Rounding>(s: &str) -> Result<i128, DecimalError> {
+    let (negative, digits) = match s.as_bytes().first() {
+        Some(b'-') => (true, &s[1..]),
+        Some(b'+') => (false, &s[1..]),
+        _ => (false, s),
+    };
+    let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+    if int_part.is_empty() && frac_part.is_empty()
+        || !int_part.bytes().all(|b| b.is_ascii_digit())
+        || !frac_part.bytes().all(|b| b.is_ascii_digit())
     {
-        serializer.serialize_str(&self.0.unix_timestamp_nanos().to_string())
+        return Err(DecimalError::Invalid);
+    }
+
+    let units: i128 = if int_part.is_empty() {
+        0
+    } else {
+        int_part.parse().map_err(|_| DecimalError::OutOfRange)?
+    };
+    let mut unix_nano = units
+        .checked_mul(U::NANOS)
+        .ok_or(DecimalError::OutOfRange)?;
+
+    let mut scale = U::NANOS;
+    let mut frac_digits = frac_part.bytes().map(|b| i128::from(b - b'0'));
+    while scale > 1 {
+        scale /= 10;
+        unix_nano = unix_nano
+            .checked_add(frac_digits.next().unwrap_or(0) * scale)
+            .ok_or(DecimalError::OutOfRange)?;
+    }
+
+    // The rest only matters through its first digit and whether anything
+    // after that is non-zero, so it is squeezed into hundredths: all four
+    // rounding modes treat 0.4999 and 0.41, 0.5 and 0.50, 0.5001 and 0.51 the
+    // same way.
+    let first = frac_digits.next().unwrap_or(0);
+    let sticky = i128::from(frac_digits.any(|d| d != 0));
+    let hundredths = unix_nano
+        .checked_mul(100)
+        .and_then(|v| v.checked_add(first * 10 + sticky))
+        .ok_or(DecimalError::OutOfRange)?;
+    let signed = if negative { -hundredths } else { hundredths };
+    M::div(signed, 100).ok_or(DecimalError::Lossy)
+}
+
+// Reads a count of `U` written as `R` as nanoseconds; `what` names the
+// quantity in errors.
+struct UnitsVisitor<U, R, M = Truncate> {
+    what: &'static str,
+    unit: Markers<U, R, M>,
+}
+
+impl<U, R, M> UnitsVisitor<U, R, M> {
+    const fn new(what: &'static str) -> Self {
+        Self {
+            what,
+            unit: PhantomData,
+        }
     }
 }
 
-#[derive(Clone, Debug)]
-pub struct UnixString(pub OffsetDateTime);
+impl<'de, U: Unit, R: Repr, M: Rounding> Visitor<'de> for UnitsVisitor<U, R, M> {
+    type Value = i128;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({} in {})", R::EXPECTING, self.what, U::NAME)
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        if !R::ACCEPTS_STR {
+            return Err(E::invalid_type(Unexpected::Str(v), &self));
+        }
+        let trimmed = v.trim();
+        match parse_decimal_nanos::<U, M>(trimmed) {
+            Err(DecimalError::Invalid) => {}
+            result => return result.map_err(|err| err.into_de::<U, E>(trimmed, &self)),
+        }
+        // Exponent notation such as "1.7e9".
+        match trimmed.parse::<f64>() {
+            Ok(units) if units.is_finite() => float_units_to_nanos::<U, M>(units)
+                .map_err(|err| err.into_de::<U, E>(trimmed, &self)),
+            _ => Err(E::invalid_value(Unexpected::Str(v), &self)),
+        }
+    }
+
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> {
+        if !R::ACCEPTS_INT {
+            return Err(E::invalid_type(Unexpected::Signed(v), &self));
+        }
+        units_to_nanos::<U, E>(v.into())
+    }
+
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> {
+        if !R::ACCEPTS_INT {
+            return Err(E::invalid_type(Unexpected::Unsigned(v), &self));
+        }
+        units_to_nanos::<U, E>(v.into())
+    }
+
+    fn visit_i128<E: Error>(self, v: i128) -> Result<Self::Value, E> {
+        if !R::ACCEPTS_INT {
+            return Err(E::invalid_type(Unexpected::Other("i128"), &self));
+        }
+        units_to_nanos::<U, E>(v)
+    }
 
-impl<'de> serde::Deserialize<'de> for UnixString {
+    fn visit_u128<E: Error>(self, v: u128) -> Result<Self::Value, E> {
+        if !R::ACCEPTS_INT {
+            return Err(E::invalid_type(Unexpected::Other("u128"), &self));
+        }
+        let units = i128::try_from(v).map_err(E::custom)?;
+        units_to_nanos::<U, E>(units)
+    }
+
+    fn visit_f64<E: Error>(self, v: f64) -> Result<Self::Value, E> {
+        if !R::ACCEPTS_FLOAT {
+            return Err(E::invalid_type(Unexpected::Float(v), &self));
+        }
+        float_units_to_nanos::<U, M>(v).map_err(|err| err.into_de::<U, E>(v, &self))
+    }
+}
+
+impl<'de, U: Unit, R: Repr, T: DateTimeValue, M: Rounding> serde::Deserialize<'de>
+    for UnixTimestamp<U, R, T, M>
+{
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
-        let s = String::deserialize(deserializer)?;
-        let unix_sec: i64 = s.parse().map_err(serde::de::Error::custom)?;
-        Ok(Self(
-            OffsetDateTime::from_unix_timestamp(unix_sec).map_err(serde::de::Error::custom)?,
-        ))
+        let unix_nano = R::deserialize::<U, D, _>(
+            deserializer,
+            UnitsVisitor::<U, R, M>::new("unix timestamp"),
+        )?;
+        T::from_unix_nanos(unix_nano)
+            .map(Self::new)
+            .map_err(D::Error::custom)
     }
 }
 
-impl serde::Serialize for UnixString {
+impl<U: Unit, R: Repr, T: DateTimeValue, M: Rounding> serde::Serialize
+    for UnixTimestamp<U, R, T, M>
+{
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&self.0.unix_timestamp().to_string())
+        let unix_nano = self.0.unix_nanos().map_err(serde::ser::Error::custom)?;
+        R::serialize::<U, M, S>(unix_nano, serializer)
+    }
+}
+
+crate::impl_wrapper_traits!(UnixTimestamp<U, R, T, M>, T);
+
+impl<U, R> From<UnixTimestamp<U, R>> for OffsetDateTime {
+    fn from(timestamp: UnixTimestamp<U, R>) -> Self {
+        timestamp.0
+    }
+}
+
+// Fills the invoking module with `#[serde(with = "...")]` functions for plain
+// date-time fields (any `DateTimeValue`, or any `$bound` when given), plus
+// `option` and `vec` submodules for `Option<_>` and `Vec<_>` of them.
+// `$wrapper` is one of the jsontime wrapper types with `$t` standing for the
+// date-time type, resolved from inside the invoking module.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __jsontime_with_module_body {
+    (<$t:ident> $wrapper:ty) => {
+        $crate::__jsontime_with_module_body!(<$t: $crate::jsontime::DateTimeValue> $wrapper);
+    };
+    (<$t:ident: $bound:path> $wrapper:ty) => {
+        type Wrapper<$t> = $wrapper;
+
+        pub fn serialize<T, S>(datetime: &T, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            T: $bound,
+            S: $crate::__private::serde::Serializer,
+        {
+            $crate::__private::serde::Serialize::serialize(
+                &Wrapper::<T>::new(datetime.clone()),
+                serializer,
+            )
+        }
+
+        pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+        where
+            T: $bound,
+            D: $crate::__private::serde::Deserializer<'de>,
+        {
+            <Wrapper<T> as $crate::__private::serde::Deserialize>::deserialize(deserializer)
+                .map(Wrapper::<T>::into_inner)
+        }
+
+        pub mod option {
+            use $crate::__private::serde::{Deserialize, Serialize};
+
+            use super::Wrapper;
+
+            pub fn serialize<T, S>(datetime: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                T: $bound,
+                S: $crate::__private::serde::Serializer,
+            {
+                datetime
+                    .clone()
+                    .map(Wrapper::<T>::new)
+                    .serialize(serializer)
+            }
+
+            pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+            where
+                T: $bound,
+                D: $crate::__private::serde::Deserializer<'de>,
+            {
+                Ok(Option::<Wrapper<T>>::deserialize(deserializer)?.map(Wrapper::<T>::into_inner))
+            }
+        }
+
+        pub mod vec {
+            use $crate::__private::serde::Deserialize;
+
+            use super::Wrapper;
+
+            pub fn serialize<T, S>(datetimes: &[T], serializer: S) -> Result<S::Ok, S::Error>
+            where
+                T: $bound,
+                S: $crate::__private::serde::Serializer,
+            {
+                serializer.collect_seq(datetimes.iter().cloned().map(Wrapper::<T>::new))
+            }
+
+            pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+            where
+                T: $bound,
+                D: $crate::__private::serde::Deserializer<'de>,
+            {
+                let wrapped = Vec::<Wrapper<T>>::deserialize(deserializer)?;
+                Ok(wrapped.into_iter().map(Wrapper::<T>::into_inner).collect())
+            }
+        }
+    };
+}
+
+macro_rules! with_module {
+    ($name:ident, <$t:ident> $wrapper:ty) => {
+        pub mod $name {
+            crate::__jsontime_with_module_body!(<$t> $wrapper);
+        }
+    };
+    ($name:ident, <$t:ident> $wrapper:ty, utc: $utc_wrapper:ty) => {
+        pub mod $name {
+            crate::__jsontime_with_module_body!(<$t> $wrapper);
+
+            pub mod utc {
+                crate::__jsontime_with_module_body!(<$t> $utc_wrapper);
+            }
+        }
+    };
+}
+
+// Unix timestamp modules truncate to whole units; their `exact` submodules
+// fail on output that would lose precision instead.
+macro_rules! unix_with_module {
+    ($name:ident, $unit:ident, $repr:ident) => {
+        pub mod $name {
+            crate::__jsontime_with_module_body!(
+                <T> super::UnixTimestamp<super::$unit, super::$repr, T>
+            );
+
+            pub mod exact {
+                crate::__jsontime_with_module_body!(
+                    <T> super::super::UnixTimestamp<
+                        super::super::$unit,
+                        super::super::$repr,
+                        T,
+                        super::super::RejectLossy,
+                    >
+                );
+            }
+        }
+    };
+}
+
+unix_with_module!(unix_secs_str, Seconds, Str);
+unix_with_module!(unix_secs_int, Seconds, Int);
+unix_with_module!(unix_secs_float, Seconds, Float);
+unix_with_module!(unix_millis_str, Millis, Str);
+unix_with_module!(unix_millis_int, Millis, Int);
+unix_with_module!(unix_millis_float, Millis, Float);
+unix_with_module!(unix_micros_str, Micros, Str);
+unix_with_module!(unix_micros_int, Micros, Int);
+unix_with_module!(unix_micros_float, Micros, Float);
+unix_with_module!(unix_nanos_str, Nanos, Str);
+unix_with_module!(unix_nanos_int, Nanos, Int);
+unix_with_module!(unix_nanos_float, Nanos, Float);
+unix_with_module!(unix_secs_lenient, Seconds, Either);
+unix_with_module!(unix_millis_lenient, Millis, Either);
+unix_with_module!(unix_micros_lenient, Micros, Either);
+unix_with_module!(unix_nanos_lenient, Nanos, Either);
+
+with_module!(unix_secs_offset, <T> super::OffsetTimestamp<super::Seconds, super::Int, T>);
+with_module!(unix_millis_offset, <T> super::OffsetTimestamp<super::Millis, super::Int, T>);
+
+with_module!(
+    rfc3339,
+    <T> super::Formatted<super::Rfc3339, super::PreserveOffset, T>,
+    utc: super::super::Formatted<super::super::Rfc3339, super::super::ToUtc, T>
+);
+with_module!(
+    rfc2822,
+    <T> super::Formatted<super::Rfc2822, super::PreserveOffset, T>,
+    utc: super::super::Formatted<super::super::Rfc2822, super::super::ToUtc, T>
+);
+with_module!(
+    iso8601,
+    <T> super::Formatted<super::Iso8601, super::PreserveOffset, T>,
+    utc: super::super::Formatted<super::super::Iso8601, super::super::ToUtc, T>
+);
+with_module!(
+    iso8601_basic,
+    <T> super::Formatted<super::Iso8601Basic, super::PreserveOffset, T>,
+    utc: super::super::Formatted<super::super::Iso8601Basic, super::super::ToUtc, T>
+);
+with_module!(
+    iso8601_week,
+    <T> super::Formatted<super::Iso8601Week, super::PreserveOffset, T>,
+    utc: super::super::Formatted<super::super::Iso8601Week, super::super::ToUtc, T>
+);
+
+// For `time::Date`, `time::Time` and `time::PrimitiveDateTime` fields.
+pub mod naive {
+    crate::__jsontime_with_module_body!(<T: crate::jsontime::NaiveValue> super::Naive<T>);
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{OffsetDateTime, PrimitiveDateTime};
+
+    use super::{duration::DurationUnits, *};
+
+    fn at(unix_nanos: i128) -> OffsetDateTime {
+        OffsetDateTime::from_unix_timestamp_nanos(unix_nanos).unwrap()
+    }
+
+    // Whole units from one end of the supported range to the other.
+    fn samples<U: Unit>() -> Vec<i128> {
+        [
+            PrimitiveDateTime::MIN.assume_utc().unix_timestamp_nanos(),
+            -1_700_000_000_987_654_321,
+            -1,
+            0,
+            1,
+            1_700_000_000_123_456_789,
+            PrimitiveDateTime::MAX.assume_utc().unix_timestamp_nanos(),
+        ]
+        .into_iter()
+        .map(|unix_nanos| unix_nanos - unix_nanos.rem_euclid(U::NANOS))
+        .collect()
+    }
+
+    fn round_trip<U: Unit, R: Repr, M: Rounding>(float: bool) {
+        type Stamp<U, R, M> = UnixTimestamp<U, R, OffsetDateTime, M>;
+
+        for unix_nanos in samples::<U>() {
+            let json = match serde_json::to_string(&Stamp::<U, R, M>::new(at(unix_nanos))) {
+                Ok(json) => json,
+                // Only floats that can't hold the value exactly may refuse it.
+                Err(err) => {
+                    assert!(float && M::EXACT, "{} {}: {}", unix_nanos, U::NAME, err);
+                    continue;
+                }
+            };
+            let back = serde_json::from_str::<Stamp<U, R, M>>(&json)
+                .unwrap_or_else(|err| panic!("{} {}: {}", json, U::NAME, err));
+            let diff = (back.0.unix_timestamp_nanos() - unix_nanos).abs();
+            let tolerance = if float && !M::EXACT {
+                (unix_nanos.abs() as f64 * f64::EPSILON * 2.0) as i128 + 1
+            } else {
+                0
+            };
+            assert!(diff <= tolerance, "{} {}: off by {}", json, U::NAME, diff);
+        }
+    }
+
+    fn round_trip_modes<U: Unit, R: Repr>(float: bool) {
+        round_trip::<U, R, Truncate>(float);
+        round_trip::<U, R, Floor>(float);
+        round_trip::<U, R, Round>(float);
+        round_trip::<U, R, RejectLossy>(float);
+    }
+
+    fn round_trip_reprs<U: Unit>() {
+        round_trip_modes::<U, Str>(false);
+        round_trip_modes::<U, Int>(false);
+        round_trip_modes::<U, Float>(true);
+        round_trip_modes::<U, Either>(false);
+    }
+
+    #[test]
+    fn unix_timestamps_round_trip() {
+        round_trip_reprs::<Seconds>();
+        round_trip_reprs::<Millis>();
+        round_trip_reprs::<Micros>();
+        round_trip_reprs::<Nanos>();
+    }
+
+    fn serializes<U: Unit, R: Repr, M: Rounding>(unix_nanos: i128) -> bool {
+        serde_json::to_string(&UnixTimestamp::<U, R, OffsetDateTime, M>::new(at(
+            unix_nanos,
+        )))
+        .is_ok()
+    }
+
+    fn reads<U: Unit, R: Repr, M: Rounding>(json: &str) -> bool {
+        serde_json::from_str::<UnixTimestamp<U, R, OffsetDateTime, M>>(json).is_ok()
+    }
+
+    #[test]
+    fn reject_lossy_output() {
+        assert!(!serializes::<Seconds, Str, RejectLossy>(1));
+        assert!(!serializes::<Millis, Int, RejectLossy>(1_000_001));
+        assert!(!serializes::<Micros, Float, RejectLossy>(
+            1_700_000_000_123_456_789
+        ));
+        assert!(serializes::<Micros, Float, RejectLossy>(1_001));
+        assert!(!serializes::<Seconds, Either, RejectLossy>(1_500_000_000));
+        assert!(serializes::<Seconds, Str, Truncate>(1));
+        assert!(serializes::<Millis, Int, RejectLossy>(1_000_000));
+
+        let max = PrimitiveDateTime::MAX.assume_utc().unix_timestamp_nanos();
+        assert!(!serializes::<Nanos, Float, RejectLossy>(max));
+        assert!(serializes::<Nanos, Float, Truncate>(max));
+    }
+
+    #[test]
+    fn reject_lossy_input() {
+        assert!(!reads::<Seconds, Str, RejectLossy>(r#""1.0000000001""#));
+        assert!(reads::<Seconds, Str, Truncate>(r#""1.0000000001""#));
+
+        // The f64 keeps only about 17 of these digits.
+        assert!(!reads::<Seconds, Float, RejectLossy>(
+            "1700000000.123456789"
+        ));
+        assert!(reads::<Seconds, Float, Truncate>("1700000000.123456789"));
+        assert!(reads::<Seconds, Float, RejectLossy>("1700000000.5"));
+
+        // Past 2^53 whole floats are rounded too.
+        assert!(!reads::<Nanos, Float, RejectLossy>("1.7e18"));
+        assert!(reads::<Nanos, Float, RejectLossy>("1.7e15"));
+        assert!(reads::<Nanos, Int, RejectLossy>("1700000000000000000"));
+    }
+
+    #[test]
+    fn string_aliases_accept_numbers() {
+        let expected = at(1_700_000_000_123_000_000);
+        for json in ["1700000000123", "1700000000123.0", r#""1700000000123""#] {
+            let stamp = serde_json::from_str::<UnixMilliString>(json).unwrap();
+            assert_eq!(stamp.0, expected, "{}", json);
+        }
+        let json = serde_json::to_string(&UnixMilliString::new(expected)).unwrap();
+        assert_eq!(json, r#""1700000000123""#);
+    }
+
+    #[test]
+    fn out_of_range_decimals_fail() {
+        let huge = "170141183460469231731687303715.9";
+        assert_eq!(
+            parse_decimal_nanos::<Seconds, Truncate>(huge),
+            Err(DecimalError::OutOfRange)
+        );
+        let quoted = format!("{:?}", huge);
+        assert!(!reads::<Seconds, Str, Truncate>(&quoted));
+        assert!(!reads::<Seconds, Either, Truncate>(huge));
+        assert!(serde_json::from_str::<AutoUnixTimestamp>(&quoted).is_err());
+        assert!(serde_json::from_str::<DurationUnits<Seconds, Str>>(&quoted).is_err());
+    }
+
+    // splitmix64, so every run sweeps the same values.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = self.0;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^ (z >> 31)
+        }
+
+        fn below(&mut self, end: u128) -> u128 {
+            ((u128::from(self.next()) << 64) | u128::from(self.next())) % end
+        }
+
+        // Anywhere in the supported range, or within a few units of the
+        // epoch so that values under one unit and negative fractions come up
+        // often.
+        fn unix_nanos<U: Unit>(&mut self) -> i128 {
+            let (start, end) = if self.next() & 1 == 0 {
+                (
+                    PrimitiveDateTime::MIN.assume_utc().unix_timestamp_nanos(),
+                    PrimitiveDateTime::MAX.assume_utc().unix_timestamp_nanos(),
+                )
+            } else {
+                (-3 * U::NANOS, 3 * U::NANOS)
+            };
+            start + self.below((end - start + 1) as u128) as i128
+        }
+    }
+
+    const SWEEP: usize = 300;
+
+    // What each mode should give, worked out apart from `Rounding::div`.
+    trait Expected: Rounding {
+        fn expected(value: i128, divisor: i128) -> Option<i128>;
+    }
+
+    impl Expected for Truncate {
+        fn expected(value: i128, divisor: i128) -> Option<i128> {
+            Some(value.signum() * (value.abs() / divisor))
+        }
+    }
+
+    impl Expected for Floor {
+        fn expected(value: i128, divisor: i128) -> Option<i128> {
+            let below = value < 0 && value % divisor != 0;
+            Some(value / divisor - i128::from(below))
+        }
+    }
+
+    impl Expected for Round {
+        fn expected(value: i128, divisor: i128) -> Option<i128> {
+            Some(value.signum() * ((value.abs() * 2 + divisor) / (divisor * 2)))
+        }
+    }
+
+    impl Expected for RejectLossy {
+        fn expected(value: i128, divisor: i128) -> Option<i128> {
+            let whole = value / divisor;
+            (whole * divisor == value).then_some(whole)
+        }
+    }
+
+    fn in_range(unix_nanos: i128) -> bool {
+        OffsetDateTime::from_unix_timestamp_nanos(unix_nanos).is_ok()
+    }
+
+    fn sweep_output<U: Unit, R: Repr, M: Expected>(quoted: bool) {
+        let mut rng = Rng(U::NANOS as u64);
+        for _ in 0..SWEEP {
+            let unix_nanos = rng.unix_nanos::<U>();
+            let stamp = UnixTimestamp::<U, R, OffsetDateTime, M>::new(at(unix_nanos));
+            let json = serde_json::to_string(&stamp);
+            let Some(units) = M::expected(unix_nanos, U::NANOS) else {
+                assert!(json.is_err(), "{} {}", unix_nanos, U::NAME);
+                continue;
+            };
+            let json = json.unwrap();
+            let expected = if quoted {
+                format!("\"{}\"", units)
+            } else {
+                units.to_string()
+            };
+            assert_eq!(json, expected, "{} {}", unix_nanos, U::NAME);
+
+            // Rounding up can step past the end of the range.
+            let back = serde_json::from_str::<UnixTimestamp<U, R, OffsetDateTime, M>>(&json);
+            match back {
+                Ok(back) => assert_eq!(back.0.unix_timestamp_nanos(), units * U::NANOS),
+                Err(_) => assert!(!in_range(units * U::NANOS), "{} {}", json, U::NAME),
+            }
+        }
+    }
+
+    fn sweep_float_output<U: Unit, M: Rounding>() {
+        let mut rng = Rng(U::NANOS as u64 + 1);
+        for _ in 0..SWEEP {
+            let unix_nanos = rng.unix_nanos::<U>();
+            let stamp = UnixTimestamp::<U, Float, OffsetDateTime, M>::new(at(unix_nanos));
+            let json = match serde_json::to_string(&stamp) {
+                Ok(json) => json,
+                Err(err) => {
+                    assert!(M::EXACT, "{} {}: {}", unix_nanos, U::NAME, err);
+                    continue;
+                }
+            };
+            let back =
+                serde_json::from_str::<UnixTimestamp<U, Float, OffsetDateTime, Truncate>>(&json)
+                    .unwrap_or_else(|err| panic!("{} {}: {}", json, U::NAME, err));
+            let diff = (back.0.unix_timestamp_nanos() - unix_nanos).abs();
+            let tolerance = if M::EXACT {
+                0
+            } else {
+                (unix_nanos.abs() as f64 * f64::EPSILON * 2.0) as i128 + 1
+            };
+            assert!(diff <= tolerance, "{} {}: off by {}", json, U::NAME, diff);
+        }
+    }
+
+    // Decimal strings with up to three digits finer than a nanosecond.
+    fn sweep_input<U: Unit, R: Repr, M: Expected>() {
+        let mut rng = Rng(U::NANOS as u64 + 2);
+        let scale = U::NANOS * 1000;
+        let width = scale.ilog10() as usize;
+        for _ in 0..SWEEP {
+            let picos = rng.unix_nanos::<U>() * 1000 + rng.below(1000) as i128;
+            let sign = if picos < 0 { "-" } else { "" };
+            let (whole, fraction) = (picos.abs() / scale, picos.abs() % scale);
+            let json = format!("\"{}{}.{:0width$}\"", sign, whole, fraction);
+
+            let back = serde_json::from_str::<UnixTimestamp<U, R, OffsetDateTime, M>>(&json);
+            match M::expected(picos, 1000) {
+                Some(unix_nanos) if in_range(unix_nanos) => {
+                    let back = back.unwrap_or_else(|err| panic!("{} {}: {}", json, U::NAME, err));
+                    assert_eq!(back.0.unix_timestamp_nanos(), unix_nanos, "{}", json);
+                }
+                _ => assert!(back.is_err(), "{} {}", json, U::NAME),
+            }
+        }
+    }
+
+    fn sweep_modes<U: Unit, M: Expected>() {
+        sweep_output::<U, Str, M>(true);
+        sweep_output::<U, Int, M>(false);
+        sweep_output::<U, Either, M>(true);
+        sweep_float_output::<U, M>();
+        sweep_input::<U, Str, M>();
+        sweep_input::<U, Either, M>();
+    }
+
+    fn sweep_units<U: Unit>() {
+        sweep_modes::<U, Truncate>();
+        sweep_modes::<U, Floor>();
+        sweep_modes::<U, Round>();
+        sweep_modes::<U, RejectLossy>();
+    }
+
+    #[test]
+    fn rounding_sweep() {
+        sweep_units::<Seconds>();
+        sweep_units::<Millis>();
+        sweep_units::<Micros>();
+        sweep_units::<Nanos>();
     }
 }
@@ -0,0 +1,155 @@
+use std::{fmt, marker::PhantomData};
+
+use serde::de::{Error, Unexpected, Visitor};
+use time::{
+    format_description::BorrowedFormatItem, macros::format_description, Date, PrimitiveDateTime,
+    Time,
+};
+
+const DATE: &[BorrowedFormatItem<'_>] = format_description!("[year]-[month]-[day]");
+
+// Seconds and their fraction are optional on input: "14:30", "14:30:15",
+// "14:30:15.25".
+const TIME_PARSE: &[BorrowedFormatItem<'_>] = format_description!(
+    version = 2,
+    "[hour]:[minute][optional [:[second][optional [.[subsecond]]]]]"
+);
+const DATETIME_PARSE: &[BorrowedFormatItem<'_>] = format_description!(
+    version = 2,
+    "[year]-[month]-[day][first [T][t][ ]][hour]:[minute][optional [:[second][optional [.[subsecond]]]]]"
+);
+
+// Output always has seconds, and a fraction only when it is not zero.
+const TIME: &[BorrowedFormatItem<'_>] = format_description!("[hour]:[minute]:[second]");
+const TIME_SUBSEC: &[BorrowedFormatItem<'_>] =
+    format_description!("[hour]:[minute]:[second].[subsecond]");
+const DATETIME: &[BorrowedFormatItem<'_>] =
+    format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]");
+const DATETIME_SUBSEC: &[BorrowedFormatItem<'_>] =
+    format_description!("[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond]");
+
+// A date, time of day or date-time without an offset, as written by sources
+// that leave the time zone implicit.
+pub trait NaiveValue: Clone {
+    const EXPECTING: &'static str;
+
+    fn format(&self) -> Result<String, time::error::Format>;
+    fn parse(s: &str) -> Result<Self, time::error::Parse>;
+}
+
+// "2024-02-07"
+impl NaiveValue for Date {
+    const EXPECTING: &'static str = "a date such as \"2024-02-07\"";
+
+    fn format(&self) -> Result<String, time::error::Format> {
+        Date::format(*self, DATE)
+    }
+
+    fn parse(s: &str) -> Result<Self, time::error::Parse> {
+        Date::parse(s, DATE)
+    }
+}
+
+// "14:30:00"
+impl NaiveValue for Time {
+    const EXPECTING: &'static str = "a time of day such as \"14:30\"";
+
+    fn format(&self) -> Result<String, time::error::Format> {
+        if self.nanosecond() == 0 {
+            Time::format(*self, TIME)
+        } else {
+            Time::format(*self, TIME_SUBSEC)
+        }
+    }
+
+    fn parse(s: &str) -> Result<Self, time::error::Parse> {
+        Time::parse(s, TIME_PARSE)
+    }
+}
+
+// "2024-02-07T14:30:00"; a space in place of the 'T' is accepted on input.
+impl NaiveValue for PrimitiveDateTime {
+    const EXPECTING: &'static str = "a date-time without offset such as \"2024-02-07T14:30\"";
+
+    fn format(&self) -> Result<String, time::error::Format> {
+        if self.nanosecond() == 0 {
+            PrimitiveDateTime::format(*self, DATETIME)
+        } else {
+            PrimitiveDateTime::format(*self, DATETIME_SUBSEC)
+        }
+    }
+
+    fn parse(s: &str) -> Result<Self, time::error::Parse> {
+        PrimitiveDateTime::parse(s, DATETIME_PARSE)
+    }
+}
+
+pub struct Naive<T>(pub T);
+
+impl<T> Naive<T> {
+    pub const fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+pub(crate) struct NaiveVisitor<T>(PhantomData<fn() -> T>);
+
+impl<T> NaiveVisitor<T> {
+    pub(crate) const fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<'de, T: NaiveValue> Visitor<'de> for NaiveVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(T::EXPECTING)
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        T::parse(v.trim()).map_err(|err| {
+            E::custom(format_args!(
+                "invalid value: string {:?}, expected {}: {}",
+                v,
+                T::EXPECTING,
+                err
+            ))
+        })
+    }
+
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        let s =
+            std::str::from_utf8(v).map_err(|_| E::invalid_value(Unexpected::Bytes(v), &self))?;
+        self.visit_str(s)
+    }
+}
+
+impl<'de, T: NaiveValue> serde::Deserialize<'de> for Naive<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer
+            .deserialize_str(NaiveVisitor::new())
+            .map(Self::new)
+    }
+}
+
+impl<T: NaiveValue> serde::Serialize for Naive<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::Error;
+
+        let formatted = self.0.format().map_err(S::Error::custom)?;
+        serializer.serialize_str(&formatted)
+    }
+}
+
+crate::impl_wrapper_traits!(Naive<T>, T);
@@ -0,0 +1,178 @@
+use std::fmt;
+
+use time::OffsetDateTime;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OutOfRangeError {
+    target: &'static str,
+}
+
+impl OutOfRangeError {
+    pub const fn new(target: &'static str) -> Self {
+        Self { target }
+    }
+}
+
+impl fmt::Display for OutOfRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "date-time is out of range for {}", self.target)
+    }
+}
+
+impl std::error::Error for OutOfRangeError {}
+
+// A date-time type the jsontime wrappers and `with` modules can work with.
+// `OffsetDateTime` is always available; `chrono::DateTime<Utc>`,
+// `chrono::DateTime<FixedOffset>`, `chrono::NaiveDateTime` (taken as UTC) and
+// `jiff::Timestamp` come with the `chrono` and `jiff` features.
+pub trait DateTimeValue: Clone {
+    fn from_offset_datetime(datetime: OffsetDateTime) -> Result<Self, OutOfRangeError>;
+    fn to_offset_datetime(&self) -> Result<OffsetDateTime, OutOfRangeError>;
+
+    fn from_unix_nanos(unix_nano: i128) -> Result<Self, OutOfRangeError> {
+        let datetime = OffsetDateTime::from_unix_timestamp_nanos(unix_nano)
+            .map_err(|_| OutOfRangeError::new("time::OffsetDateTime"))?;
+        Self::from_offset_datetime(datetime)
+    }
+
+    fn unix_nanos(&self) -> Result<i128, OutOfRangeError> {
+        Ok(self.to_offset_datetime()?.unix_timestamp_nanos())
+    }
+}
+
+impl DateTimeValue for OffsetDateTime {
+    fn from_offset_datetime(datetime: OffsetDateTime) -> Result<Self, OutOfRangeError> {
+        Ok(datetime)
+    }
+
+    fn to_offset_datetime(&self) -> Result<OffsetDateTime, OutOfRangeError> {
+        Ok(*self)
+    }
+
+    fn unix_nanos(&self) -> Result<i128, OutOfRangeError> {
+        Ok(self.unix_timestamp_nanos())
+    }
+}
+
+// Goes through `OffsetDateTime`, so the offset is kept where both sides have
+// one.
+pub fn convert_datetime<A, B>(datetime: &A) -> Result<B, OutOfRangeError>
+where
+    A: DateTimeValue,
+    B: DateTimeValue,
+{
+    B::from_offset_datetime(datetime.to_offset_datetime()?)
+}
+
+#[cfg(feature = "chrono")]
+mod chrono_impls {
+    use chrono::{DateTime, FixedOffset, NaiveDateTime, Utc};
+    use time::{OffsetDateTime, UtcOffset};
+
+    use super::{DateTimeValue, OutOfRangeError};
+
+    const NANOS_PER_SECOND: i128 = 1_000_000_000;
+
+    fn utc_from_unix_nanos(unix_nano: i128) -> Result<DateTime<Utc>, OutOfRangeError> {
+        let secs = i64::try_from(unix_nano.div_euclid(NANOS_PER_SECOND))
+            .map_err(|_| OutOfRangeError::new("chrono::DateTime"))?;
+        let nanos = unix_nano.rem_euclid(NANOS_PER_SECOND) as u32;
+        DateTime::from_timestamp(secs, nanos).ok_or(OutOfRangeError::new("chrono::DateTime"))
+    }
+
+    fn utc_unix_nanos(datetime: &DateTime<Utc>) -> i128 {
+        i128::from(datetime.timestamp()) * NANOS_PER_SECOND
+            + i128::from(datetime.timestamp_subsec_nanos())
+    }
+
+    fn utc_to_offset_datetime(datetime: &DateTime<Utc>) -> Result<OffsetDateTime, OutOfRangeError> {
+        OffsetDateTime::from_unix_timestamp_nanos(utc_unix_nanos(datetime))
+            .map_err(|_| OutOfRangeError::new("time::OffsetDateTime"))
+    }
+
+    impl DateTimeValue for DateTime<Utc> {
+        fn from_offset_datetime(datetime: OffsetDateTime) -> Result<Self, OutOfRangeError> {
+            utc_from_unix_nanos(datetime.unix_timestamp_nanos())
+        }
+
+        fn to_offset_datetime(&self) -> Result<OffsetDateTime, OutOfRangeError> {
+            utc_to_offset_datetime(self)
+        }
+
+        fn from_unix_nanos(unix_nano: i128) -> Result<Self, OutOfRangeError> {
+            utc_from_unix_nanos(unix_nano)
+        }
+
+        fn unix_nanos(&self) -> Result<i128, OutOfRangeError> {
+            Ok(utc_unix_nanos(self))
+        }
+    }
+
+    impl DateTimeValue for DateTime<FixedOffset> {
+        fn from_offset_datetime(datetime: OffsetDateTime) -> Result<Self, OutOfRangeError> {
+            let offset = FixedOffset::east_opt(datetime.offset().whole_seconds())
+                .ok_or(OutOfRangeError::new("chrono::FixedOffset"))?;
+            Ok(utc_from_unix_nanos(datetime.unix_timestamp_nanos())?.with_timezone(&offset))
+        }
+
+        fn to_offset_datetime(&self) -> Result<OffsetDateTime, OutOfRangeError> {
+            let offset = UtcOffset::from_whole_seconds(self.offset().local_minus_utc())
+                .map_err(|_| OutOfRangeError::new("time::UtcOffset"))?;
+            Ok(utc_to_offset_datetime(&self.with_timezone(&Utc))?.to_offset(offset))
+        }
+
+        fn from_unix_nanos(unix_nano: i128) -> Result<Self, OutOfRangeError> {
+            Ok(utc_from_unix_nanos(unix_nano)?.fixed_offset())
+        }
+
+        fn unix_nanos(&self) -> Result<i128, OutOfRangeError> {
+            Ok(utc_unix_nanos(&self.with_timezone(&Utc)))
+        }
+    }
+
+    impl DateTimeValue for NaiveDateTime {
+        fn from_offset_datetime(datetime: OffsetDateTime) -> Result<Self, OutOfRangeError> {
+            Ok(utc_from_unix_nanos(datetime.unix_timestamp_nanos())?.naive_utc())
+        }
+
+        fn to_offset_datetime(&self) -> Result<OffsetDateTime, OutOfRangeError> {
+            utc_to_offset_datetime(&self.and_utc())
+        }
+
+        fn from_unix_nanos(unix_nano: i128) -> Result<Self, OutOfRangeError> {
+            Ok(utc_from_unix_nanos(unix_nano)?.naive_utc())
+        }
+
+        fn unix_nanos(&self) -> Result<i128, OutOfRangeError> {
+            Ok(utc_unix_nanos(&self.and_utc()))
+        }
+    }
+}
+
+#[cfg(feature = "jiff")]
+mod jiff_impls {
+    use jiff::Timestamp;
+    use time::OffsetDateTime;
+
+    use super::{DateTimeValue, OutOfRangeError};
+
+    impl DateTimeValue for Timestamp {
+        fn from_offset_datetime(datetime: OffsetDateTime) -> Result<Self, OutOfRangeError> {
+            Self::from_unix_nanos(datetime.unix_timestamp_nanos())
+        }
+
+        fn to_offset_datetime(&self) -> Result<OffsetDateTime, OutOfRangeError> {
+            OffsetDateTime::from_unix_timestamp_nanos(self.as_nanosecond())
+                .map_err(|_| OutOfRangeError::new("time::OffsetDateTime"))
+        }
+
+        fn from_unix_nanos(unix_nano: i128) -> Result<Self, OutOfRangeError> {
+            Timestamp::from_nanosecond(unix_nano)
+                .map_err(|_| OutOfRangeError::new("jiff::Timestamp"))
+        }
+
+        fn unix_nanos(&self) -> Result<i128, OutOfRangeError> {
+            Ok(self.as_nanosecond())
+        }
+    }
+}
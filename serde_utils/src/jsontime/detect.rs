@@ -0,0 +1,333 @@
+use std::{fmt, marker::PhantomData};
+
+use serde::de::{Error, Unexpected, Visitor};
+use time::OffsetDateTime;
+
+use super::{
+    parse_decimal_nanos, DateTimeValue, DecimalError, Micros, Millis, Nanos, Seconds, Truncate,
+    Unit,
+};
+
+// The range of dates a feed can plausibly send, in unix seconds (start
+// inclusive, end exclusive). The unit whose reading lands inside it wins, so
+// the window has to span less than a factor of 1000 around the epoch.
+pub trait PlausibleWindow {
+    const START: i64;
+    const END: i64;
+}
+
+// 1990-01-01 to 2100-01-01.
+#[derive(Clone, Copy, Debug)]
+pub enum DefaultWindow {}
+
+impl PlausibleWindow for DefaultWindow {
+    const START: i64 = 631_152_000;
+    const END: i64 = 4_102_444_800;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DetectedUnit {
+    Seconds,
+    Millis,
+    Micros,
+    Nanos,
+}
+
+impl DetectedUnit {
+    const ALL: [Self; 4] = [Self::Seconds, Self::Millis, Self::Micros, Self::Nanos];
+
+    pub const fn nanos(self) -> i128 {
+        match self {
+            Self::Seconds => Seconds::NANOS,
+            Self::Millis => Millis::NANOS,
+            Self::Micros => Micros::NANOS,
+            Self::Nanos => Nanos::NANOS,
+        }
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Seconds => Seconds::NAME,
+            Self::Millis => Millis::NAME,
+            Self::Micros => Micros::NAME,
+            Self::Nanos => Nanos::NAME,
+        }
+    }
+
+    fn parse_nanos(self, s: &str) -> Result<i128, DecimalError> {
+        match self {
+            Self::Seconds => parse_decimal_nanos::<Seconds, Truncate>(s),
+            Self::Millis => parse_decimal_nanos::<Millis, Truncate>(s),
+            Self::Micros => parse_decimal_nanos::<Micros, Truncate>(s),
+            Self::Nanos => parse_decimal_nanos::<Nanos, Truncate>(s),
+        }
+    }
+}
+
+impl fmt::Display for DetectedUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+// A unix timestamp in seconds, milliseconds, microseconds or nanoseconds,
+// whichever puts it inside the window `W`. Values that fit no unit are still
+// accepted, read in the unit that brings them closest to the window, and
+// flagged as `suspicious`.
+//
+// Serializes back as an integer in the detected unit.
+pub struct AutoUnixTimestamp<W = DefaultWindow, T = OffsetDateTime> {
+    pub datetime: T,
+    pub unit: DetectedUnit,
+    pub suspicious: bool,
+    window: PhantomData<fn() -> W>,
+}
+
+impl<W, T> AutoUnixTimestamp<W, T> {
+    pub const fn new(datetime: T, unit: DetectedUnit) -> Self {
+        Self {
+            datetime,
+            unit,
+            suspicious: false,
+            window: PhantomData,
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.datetime
+    }
+}
+
+// Roughly how many powers of ten of seconds `unix_nano` lies outside the
+// window; zero inside it.
+fn distance<W: PlausibleWindow>(unix_nano: i128) -> u32 {
+    let secs = unix_nano.div_euclid(Seconds::NANOS);
+    let (start, end) = (i128::from(W::START), i128::from(W::END));
+    let gap = if secs < start {
+        start - secs
+    } else if secs >= end {
+        secs - end + 1
+    } else {
+        return 0;
+    };
+    gap.unsigned_abs().ilog10() + 1
+}
+
+// Picks the unit for a plain decimal number, and whether it is suspicious.
+fn detect<W: PlausibleWindow>(s: &str) -> Result<(DetectedUnit, bool), DecimalError> {
+    let mut best: Option<(DetectedUnit, u32)> = None;
+    for unit in DetectedUnit::ALL {
+        let unix_nano = match unit.parse_nanos(s) {
+            Ok(unix_nano) => unix_nano,
+            Err(DecimalError::OutOfRange) => continue,
+            Err(err) => return Err(err),
+        };
+        let distance = distance::<W>(unix_nano);
+        if distance == 0 {
+            return Ok((unit, false));
+        }
+        if best.is_none_or(|(_, best)| distance < best) {
+            best = Some((unit, distance));
+        }
+    }
+    best.map(|(unit, _)| (unit, true))
+        .ok_or(DecimalError::OutOfRange)
+}
+
+struct AutoVisitor<W>(PhantomData<fn() -> W>);
+
+impl<'de, W: PlausibleWindow> Visitor<'de> for AutoVisitor<W> {
+    type Value = (i128, DetectedUnit, bool);
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("an integer, a float or a numeric string (unix timestamp in any unit)")
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        let trimmed = v.trim();
+        // Exponent notation such as "1.7e9" goes through its plain decimal
+        // form.
+        let decimal = match trimmed.parse::<f64>() {
+            Ok(value) if trimmed.contains(['e', 'E']) && value.is_finite() => value.to_string(),
+            _ => trimmed.to_owned(),
+        };
+        let (unit, suspicious) = detect::<W>(&decimal).map_err(|err| match err {
+            DecimalError::Invalid => E::invalid_value(Unexpected::Str(v), &self),
+            _ => E::custom(format_args!("unix timestamp {} is out of range", v)),
+        })?;
+        let unix_nano = unit
+            .parse_nanos(&decimal)
+            .map_err(|_| E::custom(format_args!("unix timestamp {} is out of range", v)))?;
+        Ok((unix_nano, unit, suspicious))
+    }
+
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> {
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> {
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_i128<E: Error>(self, v: i128) -> Result<Self::Value, E> {
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_u128<E: Error>(self, v: u128) -> Result<Self::Value, E> {
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_f64<E: Error>(self, v: f64) -> Result<Self::Value, E> {
+        if !v.is_finite() {
+            return Err(E::invalid_value(Unexpected::Float(v), &self));
+        }
+        self.visit_str(&v.to_string())
+    }
+}
+
+impl<'de, W: PlausibleWindow, T: DateTimeValue> serde::Deserialize<'de>
+    for AutoUnixTimestamp<W, T>
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let (unix_nano, unit, suspicious) =
+            deserializer.deserialize_any(AutoVisitor::<W>(PhantomData))?;
+        let datetime = T::from_unix_nanos(unix_nano).map_err(D::Error::custom)?;
+        Ok(Self {
+            suspicious,
+            ..Self::new(datetime, unit)
+        })
+    }
+}
+
+impl<W, T: DateTimeValue> serde::Serialize for AutoUnixTimestamp<W, T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let unix_nano = self
+            .datetime
+            .unix_nanos()
+            .map_err(serde::ser::Error::custom)?;
+        let value = unix_nano / self.unit.nanos();
+        match i64::try_from(value) {
+            Ok(value) => serializer.serialize_i64(value),
+            Err(_) => serializer.serialize_i128(value),
+        }
+    }
+}
+
+impl<W, T: Clone> Clone for AutoUnixTimestamp<W, T> {
+    fn clone(&self) -> Self {
+        Self {
+            datetime: self.datetime.clone(),
+            unit: self.unit,
+            suspicious: self.suspicious,
+            window: PhantomData,
+        }
+    }
+}
+
+impl<W, T: Copy> Copy for AutoUnixTimestamp<W, T> {}
+
+impl<W, T: fmt::Debug> fmt::Debug for AutoUnixTimestamp<W, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AutoUnixTimestamp")
+            .field("datetime", &self.datetime)
+            .field("unit", &self.unit)
+            .field("suspicious", &self.suspicious)
+            .finish()
+    }
+}
+
+impl<W, T: PartialEq> PartialEq for AutoUnixTimestamp<W, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.datetime == other.datetime
+            && self.unit == other.unit
+            && self.suspicious == other.suspicious
+    }
+}
+
+impl<W, T: Eq> Eq for AutoUnixTimestamp<W, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    enum FromEpoch {}
+
+    impl PlausibleWindow for FromEpoch {
+        const START: i64 = 0;
+        const END: i64 = 4_102_444_800;
+    }
+
+    enum BeforeEpoch {}
+
+    impl PlausibleWindow for BeforeEpoch {
+        const START: i64 = -4_102_444_800;
+        const END: i64 = 0;
+    }
+
+    fn read<W: PlausibleWindow>(json: &str) -> Result<AutoUnixTimestamp<W>, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    fn detected(json: &str) -> (DetectedUnit, bool) {
+        let stamp = read::<DefaultWindow>(json).unwrap();
+        (stamp.unit, stamp.suspicious)
+    }
+
+    #[test]
+    fn picks_the_unit_inside_the_window() {
+        assert_eq!(detected("1700000000"), (DetectedUnit::Seconds, false));
+        assert_eq!(detected("1700000000123"), (DetectedUnit::Millis, false));
+        assert_eq!(
+            detected(r#""1700000000123456""#),
+            (DetectedUnit::Micros, false)
+        );
+        assert_eq!(
+            detected("1700000000123456789"),
+            (DetectedUnit::Nanos, false)
+        );
+        assert_eq!(detected("1700000000.5"), (DetectedUnit::Seconds, false));
+        assert_eq!(detected(r#""1.7e12""#), (DetectedUnit::Millis, false));
+
+        let stamp = read::<DefaultWindow>("1700000000123").unwrap();
+        assert_eq!(
+            stamp.datetime.unix_timestamp_nanos(),
+            1_700_000_000_123_000_000
+        );
+        assert_eq!(serde_json::to_string(&stamp).unwrap(), "1700000000123");
+    }
+
+    #[test]
+    fn flags_values_outside_the_window() {
+        assert_eq!(detected("0"), (DetectedUnit::Seconds, true));
+        assert_eq!(detected("-5"), (DetectedUnit::Seconds, true));
+        assert_eq!(
+            detected("100000000000000000000"),
+            (DetectedUnit::Nanos, true)
+        );
+    }
+
+    #[test]
+    fn windows_touching_zero() {
+        assert!(read::<FromEpoch>("-5").unwrap().suspicious);
+        assert!(!read::<FromEpoch>("0").unwrap().suspicious);
+        assert!(read::<BeforeEpoch>("0").unwrap().suspicious);
+        assert!(read::<BeforeEpoch>("5").unwrap().suspicious);
+        assert!(!read::<BeforeEpoch>("-5").unwrap().suspicious);
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        assert!(read::<DefaultWindow>(r#""""#).is_err());
+        assert!(read::<DefaultWindow>(r#""-""#).is_err());
+        assert!(read::<DefaultWindow>(r#""12abc""#).is_err());
+        assert!(read::<DefaultWindow>("true").is_err());
+        assert!(read::<DefaultWindow>(r#""1e400""#).is_err());
+        assert!(read::<DefaultWindow>(r#""999999999999999999999999999999999999999""#).is_err());
+    }
+}
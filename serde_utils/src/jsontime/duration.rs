@@ -0,0 +1,475 @@
+use std::{fmt, marker::PhantomData, time::Duration as StdDuration};
+
+use serde::de::{Error, Unexpected, Visitor};
+
+use super::{Float, Int, Micros, Millis, Nanos, Repr, Seconds, Str, Truncate, Unit, UnitsVisitor};
+
+const NANOS_PER_SECOND: i128 = 1_000_000_000;
+const NANOS_PER_MINUTE: i128 = 60 * NANOS_PER_SECOND;
+const NANOS_PER_HOUR: i128 = 60 * NANOS_PER_MINUTE;
+const NANOS_PER_DAY: i128 = 24 * NANOS_PER_HOUR;
+const NANOS_PER_WEEK: i128 = 7 * NANOS_PER_DAY;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DurationRangeError {
+    target: &'static str,
+}
+
+impl fmt::Display for DurationRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "duration is out of range for {}", self.target)
+    }
+}
+
+impl std::error::Error for DurationRangeError {}
+
+// `std::time::Duration` rejects negative values; `time::Duration` is signed.
+pub trait DurationValue: Clone {
+    fn from_nanos(nanos: i128) -> Result<Self, DurationRangeError>;
+    fn as_nanos(&self) -> i128;
+}
+
+impl DurationValue for StdDuration {
+    fn from_nanos(nanos: i128) -> Result<Self, DurationRangeError> {
+        let error = DurationRangeError {
+            target: "std::time::Duration",
+        };
+        let secs = u64::try_from(nanos.div_euclid(NANOS_PER_SECOND)).map_err(|_| error)?;
+        let subsec_nanos = nanos.rem_euclid(NANOS_PER_SECOND) as u32;
+        Ok(StdDuration::new(secs, subsec_nanos))
+    }
+
+    fn as_nanos(&self) -> i128 {
+        StdDuration::as_nanos(self) as i128
+    }
+}
+
+impl DurationValue for time::Duration {
+    fn from_nanos(nanos: i128) -> Result<Self, DurationRangeError> {
+        let secs = i64::try_from(nanos / NANOS_PER_SECOND).map_err(|_| DurationRangeError {
+            target: "time::Duration",
+        })?;
+        let subsec_nanos = (nanos % NANOS_PER_SECOND) as i32;
+        Ok(time::Duration::new(secs, subsec_nanos))
+    }
+
+    fn as_nanos(&self) -> i128 {
+        self.whole_nanoseconds()
+    }
+}
+
+// A count of `U`, written in the `R` representation: `90`, `"90"`, `1.5`.
+// Like the timestamp types, `Either` accepts any of those forms on input.
+pub struct DurationUnits<U, R = Int, D = StdDuration>(pub D, PhantomData<fn() -> (U, R)>);
+
+// Humantime strings: "1h 30m", "250ms", "2days 4h". A leading '-' is accepted
+// for signed durations.
+pub struct HumanDuration<D = StdDuration>(pub D);
+
+// ISO 8601 durations with weeks, days, hours, minutes and (fractional)
+// seconds: "PT5M", "P1DT2H", "PT0.25S", "-PT30S". Years and months are
+// rejected since their length is not fixed. Days are taken as 24 hours and
+// the output only uses hours, minutes and seconds ("PT26H").
+pub struct Iso8601Duration<D = StdDuration>(pub D);
+
+impl<U, R, D> DurationUnits<U, R, D> {
+    pub const fn new(duration: D) -> Self {
+        Self(duration, PhantomData)
+    }
+
+    pub fn into_inner(self) -> D {
+        self.0
+    }
+}
+
+impl<D> HumanDuration<D> {
+    pub const fn new(duration: D) -> Self {
+        Self(duration)
+    }
+
+    pub fn into_inner(self) -> D {
+        self.0
+    }
+}
+
+impl<D> Iso8601Duration<D> {
+    pub const fn new(duration: D) -> Self {
+        Self(duration)
+    }
+
+    pub fn into_inner(self) -> D {
+        self.0
+    }
+}
+
+impl<'de, U: Unit, R: Repr, D: DurationValue> serde::Deserialize<'de> for DurationUnits<U, R, D> {
+    fn deserialize<De>(deserializer: De) -> Result<Self, De::Error>
+    where
+        De: serde::Deserializer<'de>,
+    {
+        let nanos = R::deserialize::<U, De, _>(
+            deserializer,
+            UnitsVisitor::<U, R, Truncate>::new("duration"),
+        )?;
+        D::from_nanos(nanos)
+            .map(Self::new)
+            .map_err(De::Error::custom)
+    }
+}
+
+impl<U: Unit, R: Repr, D: DurationValue> serde::Serialize for DurationUnits<U, R, D> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        R::serialize::<U, Truncate, S>(self.0.as_nanos(), serializer)
+    }
+}
+
+struct HumanVisitor;
+
+impl<'de> Visitor<'de> for HumanVisitor {
+    type Value = i128;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a duration such as \"1h 30m\"")
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        let trimmed = v.trim();
+        let (negative, unsigned) = match trimmed.strip_prefix('-') {
+            Some(rest) => (true, rest.trim_start()),
+            None => (false, trimmed),
+        };
+        let duration = ::humantime::parse_duration(unsigned)
+            .map_err(|err| E::custom(format_args!("invalid duration {:?}: {}", v, err)))?;
+        let nanos = DurationValue::as_nanos(&duration);
+        Ok(if negative { -nanos } else { nanos })
+    }
+}
+
+impl<'de, D: DurationValue> serde::Deserialize<'de> for HumanDuration<D> {
+    fn deserialize<De>(deserializer: De) -> Result<Self, De::Error>
+    where
+        De: serde::Deserializer<'de>,
+    {
+        let nanos = deserializer.deserialize_str(HumanVisitor)?;
+        D::from_nanos(nanos)
+            .map(Self::new)
+            .map_err(De::Error::custom)
+    }
+}
+
+struct HumanDisplay(i128);
+
+impl fmt::Display for HumanDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 < 0 {
+            f.write_str("-")?;
+        }
+        let nanos = self.0.unsigned_abs();
+        let secs = u64::try_from(nanos / NANOS_PER_SECOND as u128).map_err(|_| fmt::Error)?;
+        let duration = StdDuration::new(secs, (nanos % NANOS_PER_SECOND as u128) as u32);
+        fmt::Display::fmt(&::humantime::format_duration(duration), f)
+    }
+}
+
+impl<D: DurationValue> serde::Serialize for HumanDuration<D> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(&HumanDisplay(self.0.as_nanos()))
+    }
+}
+
+// `digits` is "12", "1.5" or "0,25"; the result is that many `scale`
+// nanoseconds. Fractions finer than a nanosecond are dropped.
+fn decimal_nanos(digits: &str, scale: i128) -> Option<i128> {
+    let (int_part, frac_part) = match digits.find(['.', ',']) {
+        Some(i) => (&digits[..i], &digits[i + 1..]),
+        None => (digits, ""),
+    };
+    if int_part.is_empty() && frac_part.is_empty()
+        || !int_part.bytes().all(|b| b.is_ascii_digit())
+        || !frac_part.bytes().all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let whole: i128 = if int_part.is_empty() {
+        0
+    } else {
+        int_part.parse().ok()?
+    };
+    let mut nanos = whole.checked_mul(scale)?;
+    let mut place = scale;
+    for digit in frac_part.bytes() {
+        place /= 10;
+        if place == 0 {
+            break;
+        }
+        nanos = nanos.checked_add(i128::from(digit - b'0') * place)?;
+    }
+    Some(nanos)
+}
+
+// Sums "<number><designator>" components, which must appear in the order of
+// `designators` and at most once each.
+fn iso8601_components(part: &str, designators: &[(char, i128)]) -> Option<i128> {
+    let mut total: i128 = 0;
+    let mut rest = part;
+    let mut allowed = designators;
+    while !rest.is_empty() {
+        let end = rest.find(|c: char| c.is_ascii_alphabetic())?;
+        let designator = rest[end..].chars().next()?.to_ascii_uppercase();
+        let position = allowed.iter().position(|(d, _)| *d == designator)?;
+        let nanos = decimal_nanos(&rest[..end], allowed[position].1)?;
+        total = total.checked_add(nanos)?;
+        allowed = &allowed[position + 1..];
+        rest = &rest[end + 1..];
+    }
+    Some(total)
+}
+
+fn parse_iso8601_duration(s: &str) -> Option<i128> {
+    let (negative, unsigned) = match s.as_bytes().first()? {
+        b'-' => (true, &s[1..]),
+        b'+' => (false, &s[1..]),
+        _ => (false, s),
+    };
+    let body = unsigned
+        .strip_prefix('P')
+        .or_else(|| unsigned.strip_prefix('p'))?;
+    let (date, time) = match body.find(['T', 't']) {
+        Some(i) => (&body[..i], Some(&body[i + 1..])),
+        None => (body, None),
+    };
+    if date.is_empty() && time.is_none() || time == Some("") {
+        return None;
+    }
+
+    let mut nanos = iso8601_components(date, &[('W', NANOS_PER_WEEK), ('D', NANOS_PER_DAY)])?;
+    if let Some(time) = time {
+        let time_nanos = iso8601_components(
+            time,
+            &[
+                ('H', NANOS_PER_HOUR),
+                ('M', NANOS_PER_MINUTE),
+                ('S', NANOS_PER_SECOND),
+            ],
+        )?;
+        nanos = nanos.checked_add(time_nanos)?;
+    }
+    Some(if negative { -nanos } else { nanos })
+}
+
+struct Iso8601Visitor;
+
+impl<'de> Visitor<'de> for Iso8601Visitor {
+    type Value = i128;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("an ISO 8601 duration such as \"PT5M\" (without years or months)")
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        parse_iso8601_duration(v.trim()).ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+    }
+}
+
+impl<'de, D: DurationValue> serde::Deserialize<'de> for Iso8601Duration<D> {
+    fn deserialize<De>(deserializer: De) -> Result<Self, De::Error>
+    where
+        De: serde::Deserializer<'de>,
+    {
+        let nanos = deserializer.deserialize_str(Iso8601Visitor)?;
+        D::from_nanos(nanos)
+            .map(Self::new)
+            .map_err(De::Error::custom)
+    }
+}
+
+struct Iso8601Display(i128);
+
+impl fmt::Display for Iso8601Display {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 == 0 {
+            return f.write_str("PT0S");
+        }
+        if self.0 < 0 {
+            f.write_str("-")?;
+        }
+        f.write_str("PT")?;
+
+        let nanos = self.0.unsigned_abs();
+        let hours = nanos / NANOS_PER_HOUR as u128;
+        let minutes = nanos % NANOS_PER_HOUR as u128 / NANOS_PER_MINUTE as u128;
+        let seconds = nanos % NANOS_PER_MINUTE as u128 / NANOS_PER_SECOND as u128;
+        let subsec_nanos = nanos % NANOS_PER_SECOND as u128;
+
+        if hours > 0 {
+            write!(f, "{}H", hours)?;
+        }
+        if minutes > 0 {
+            write!(f, "{}M", minutes)?;
+        }
+        if subsec_nanos > 0 {
+            let fraction = format!("{:09}", subsec_nanos);
+            write!(f, "{}.{}S", seconds, fraction.trim_end_matches('0'))?;
+        } else if seconds > 0 {
+            write!(f, "{}S", seconds)?;
+        }
+        Ok(())
+    }
+}
+
+impl<D: DurationValue> serde::Serialize for Iso8601Duration<D> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(&Iso8601Display(self.0.as_nanos()))
+    }
+}
+
+crate::impl_wrapper_traits!(DurationUnits<U, R, D>, D);
+crate::impl_wrapper_traits!(HumanDuration<D>, D);
+crate::impl_wrapper_traits!(Iso8601Duration<D>, D);
+
+// `#[serde(with = "...")]` modules for `std::time::Duration` and
+// `time::Duration` fields, each with `option` and `vec` submodules.
+macro_rules! duration_with_module {
+    ($name:ident, <$d:ident> $wrapper:ty) => {
+        pub mod $name {
+            crate::__jsontime_with_module_body!(
+                <$d: crate::jsontime::duration::DurationValue> $wrapper
+            );
+        }
+    };
+}
+
+duration_with_module!(secs, <T> super::DurationUnits<super::Seconds, super::Int, T>);
+duration_with_module!(secs_str, <T> super::DurationUnits<super::Seconds, super::Str, T>);
+duration_with_module!(secs_float, <T> super::DurationUnits<super::Seconds, super::Float, T>);
+duration_with_module!(millis, <T> super::DurationUnits<super::Millis, super::Int, T>);
+duration_with_module!(millis_str, <T> super::DurationUnits<super::Millis, super::Str, T>);
+duration_with_module!(millis_float, <T> super::DurationUnits<super::Millis, super::Float, T>);
+duration_with_module!(micros, <T> super::DurationUnits<super::Micros, super::Int, T>);
+duration_with_module!(micros_str, <T> super::DurationUnits<super::Micros, super::Str, T>);
+duration_with_module!(nanos, <T> super::DurationUnits<super::Nanos, super::Int, T>);
+duration_with_module!(nanos_str, <T> super::DurationUnits<super::Nanos, super::Str, T>);
+duration_with_module!(humantime, <T> super::HumanDuration<T>);
+duration_with_module!(iso8601, <T> super::Iso8601Duration<T>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECOND: i128 = NANOS_PER_SECOND;
+
+    #[test]
+    fn iso8601_parsing() {
+        let parse = parse_iso8601_duration;
+        assert_eq!(parse("PT5M"), Some(5 * NANOS_PER_MINUTE));
+        assert_eq!(parse("P1DT2H"), Some(NANOS_PER_DAY + 2 * NANOS_PER_HOUR));
+        assert_eq!(parse("P2W"), Some(2 * NANOS_PER_WEEK));
+        assert_eq!(parse("PT0.25S"), Some(SECOND / 4));
+        assert_eq!(parse("PT0,5S"), Some(SECOND / 2));
+        assert_eq!(parse("PT.5S"), Some(SECOND / 2));
+        assert_eq!(parse("pt1m"), Some(NANOS_PER_MINUTE));
+        assert_eq!(parse("-PT30S"), Some(-30 * SECOND));
+        assert_eq!(parse("+PT30S"), Some(30 * SECOND));
+        assert_eq!(parse("PT0S"), Some(0));
+        // Finer than a nanosecond.
+        assert_eq!(parse("PT0.0000000019S"), Some(1));
+    }
+
+    #[test]
+    fn iso8601_rejects() {
+        for s in [
+            "", "-", "P", "PT", "P1DT", "PT5", "PTS", "PT.S", "5M", "P1Y", "P1M", "PT1S2M",
+            "PT1H1H", "PT-5S", "--PT5S", "PT1.5.5S",
+        ] {
+            assert_eq!(parse_iso8601_duration(s), None, "{:?}", s);
+        }
+        assert_eq!(parse_iso8601_duration(&format!("PT{}H", u128::MAX)), None);
+        assert_eq!(
+            parse_iso8601_duration(&format!("PT{}H", i128::MAX / NANOS_PER_HOUR + 1)),
+            None
+        );
+    }
+
+    #[test]
+    fn iso8601_round_trip() {
+        let write = |nanos: i128| {
+            let duration = time::Duration::from_nanos(nanos).unwrap();
+            serde_json::to_string(&Iso8601Duration::new(duration)).unwrap()
+        };
+        assert_eq!(write(0), r#""PT0S""#);
+        assert_eq!(write(NANOS_PER_DAY + 2 * NANOS_PER_HOUR), r#""PT26H""#);
+        assert_eq!(write(-3 * SECOND / 2), r#""-PT1.5S""#);
+        assert_eq!(write(NANOS_PER_MINUTE + 1), r#""PT1M0.000000001S""#);
+
+        let read = |json: &str| serde_json::from_str::<Iso8601Duration<time::Duration>>(json);
+        assert_eq!(
+            read(r#""-PT1.5S""#).unwrap().0,
+            time::Duration::milliseconds(-1500)
+        );
+        assert_eq!(read(r#"" PT1M ""#).unwrap().0, time::Duration::MINUTE);
+        assert!(read(r#""P1Y""#).is_err());
+        assert!(read("30").is_err());
+
+        // Negative durations need a signed type.
+        assert!(serde_json::from_str::<Iso8601Duration>(r#""-PT1S""#).is_err());
+        assert!(serde_json::from_str::<Iso8601Duration>(r#""-PT0S""#).is_ok());
+    }
+
+    #[test]
+    fn humantime_signs() {
+        let read = |json: &str| serde_json::from_str::<HumanDuration<time::Duration>>(json);
+        assert_eq!(read(r#""1h 30m""#).unwrap().0, time::Duration::minutes(90));
+        assert_eq!(read(r#""-5s""#).unwrap().0, time::Duration::seconds(-5));
+        assert_eq!(
+            read(r#"" - 250ms""#).unwrap().0,
+            time::Duration::milliseconds(-250)
+        );
+        assert!(read(r#""""#).is_err());
+        assert!(read(r#""-""#).is_err());
+        assert!(read(r#""--5s""#).is_err());
+        assert!(read(r#""+5s""#).is_err());
+        assert!(read(r#""5""#).is_err());
+
+        assert!(serde_json::from_str::<HumanDuration>(r#""-5s""#).is_err());
+        assert_eq!(
+            serde_json::from_str::<HumanDuration>(r#""2days 4h""#)
+                .unwrap()
+                .0,
+            StdDuration::from_secs(52 * 3600)
+        );
+
+        let write = |duration: time::Duration| {
+            serde_json::to_string(&HumanDuration::new(duration)).unwrap()
+        };
+        assert_eq!(write(time::Duration::seconds(-5)), r#""-5s""#);
+        assert_eq!(write(time::Duration::minutes(90)), r#""1h 30m""#);
+        assert_eq!(write(time::Duration::ZERO), r#""0s""#);
+    }
+
+    #[test]
+    fn with_modules() {
+        let durations = [StdDuration::from_secs(90), StdDuration::from_millis(1500)];
+        let mut json = Vec::new();
+        secs::vec::serialize(&durations, &mut serde_json::Serializer::new(&mut json)).unwrap();
+        assert_eq!(json, b"[90,1]");
+
+        let mut deserializer = serde_json::Deserializer::from_str(r#"["PT1M30S","PT1.5S"]"#);
+        let read: Vec<StdDuration> = iso8601::vec::deserialize(&mut deserializer).unwrap();
+        assert_eq!(read, durations);
+
+        let mut deserializer = serde_json::Deserializer::from_str("null");
+        let read: Option<time::Duration> =
+            humantime::option::deserialize(&mut deserializer).unwrap();
+        assert_eq!(read, None);
+    }
+}
@@ -0,0 +1,285 @@
+use std::{fmt, marker::PhantomData, sync::OnceLock};
+
+use serde::de::{Error, Unexpected, Visitor};
+
+use super::DateTimeValue;
+use time::{
+    format_description::{
+        self,
+        well_known::{
+            self,
+            iso8601::{Config, DateKind, EncodedConfig},
+        },
+        OwnedFormatItem,
+    },
+    OffsetDateTime, UtcOffset,
+};
+
+pub trait Format {
+    const EXPECTING: &'static str;
+
+    fn format(datetime: OffsetDateTime) -> Result<String, time::Error>;
+    fn parse(s: &str) -> Result<OffsetDateTime, time::Error>;
+}
+
+// "2024-02-07T14:30:00+05:30"
+#[derive(Clone, Copy, Debug)]
+pub enum Rfc3339 {}
+
+impl Format for Rfc3339 {
+    const EXPECTING: &'static str = "an RFC 3339 date-time";
+
+    fn format(datetime: OffsetDateTime) -> Result<String, time::Error> {
+        Ok(datetime.format(&well_known::Rfc3339)?)
+    }
+
+    fn parse(s: &str) -> Result<OffsetDateTime, time::Error> {
+        Ok(OffsetDateTime::parse(s, &well_known::Rfc3339)?)
+    }
+}
+
+// "Wed, 07 Feb 2024 14:30:00 +0530"
+#[derive(Clone, Copy, Debug)]
+pub enum Rfc2822 {}
+
+impl Format for Rfc2822 {
+    const EXPECTING: &'static str = "an RFC 2822 date-time";
+
+    fn format(datetime: OffsetDateTime) -> Result<String, time::Error> {
+        Ok(datetime.format(&well_known::Rfc2822)?)
+    }
+
+    fn parse(s: &str) -> Result<OffsetDateTime, time::Error> {
+        Ok(OffsetDateTime::parse(s, &well_known::Rfc2822)?)
+    }
+}
+
+const ISO8601_EXTENDED: EncodedConfig = Config::DEFAULT.encode();
+const ISO8601_BASIC: EncodedConfig = Config::DEFAULT.set_use_separators(false).encode();
+const ISO8601_WEEK: EncodedConfig = Config::DEFAULT.set_date_kind(DateKind::Week).encode();
+
+fn parse_iso8601(s: &str) -> Result<OffsetDateTime, time::Error> {
+    Ok(OffsetDateTime::parse(s, &well_known::Iso8601::PARSING)?)
+}
+
+// The three ISO 8601 formats only differ in their output; all of them parse
+// any ISO 8601 date-time (calendar, ordinal or week dates, basic or extended).
+
+// "2024-02-07T14:30:00.000000000+05:30"
+#[derive(Clone, Copy, Debug)]
+pub enum Iso8601 {}
+
+impl Format for Iso8601 {
+    const EXPECTING: &'static str = "an ISO 8601 date-time";
+
+    fn format(datetime: OffsetDateTime) -> Result<String, time::Error> {
+        Ok(datetime.format(&well_known::Iso8601::<ISO8601_EXTENDED>)?)
+    }
+
+    fn parse(s: &str) -> Result<OffsetDateTime, time::Error> {
+        parse_iso8601(s)
+    }
+}
+
+// "20240207T143000.000000000+0530"
+#[derive(Clone, Copy, Debug)]
+pub enum Iso8601Basic {}
+
+impl Format for Iso8601Basic {
+    const EXPECTING: &'static str = "an ISO 8601 date-time";
+
+    fn format(datetime: OffsetDateTime) -> Result<String, time::Error> {
+        Ok(datetime.format(&well_known::Iso8601::<ISO8601_BASIC>)?)
+    }
+
+    fn parse(s: &str) -> Result<OffsetDateTime, time::Error> {
+        parse_iso8601(s)
+    }
+}
+
+// "2024-W06-3T14:30:00.000000000+05:30"
+#[derive(Clone, Copy, Debug)]
+pub enum Iso8601Week {}
+
+impl Format for Iso8601Week {
+    const EXPECTING: &'static str = "an ISO 8601 date-time";
+
+    fn format(datetime: OffsetDateTime) -> Result<String, time::Error> {
+        Ok(datetime.format(&well_known::Iso8601::<ISO8601_WEEK>)?)
+    }
+
+    fn parse(s: &str) -> Result<OffsetDateTime, time::Error> {
+        parse_iso8601(s)
+    }
+}
+
+#[doc(hidden)]
+pub fn cached_format_description(
+    cell: &'static OnceLock<Result<OwnedFormatItem, time::error::InvalidFormatDescription>>,
+    description: &'static str,
+) -> Result<&'static OwnedFormatItem, time::Error> {
+    cell.get_or_init(|| format_description::parse_owned::<2>(description))
+        .as_ref()
+        .map_err(|err| err.clone().into())
+}
+
+// Defines a module usable with `#[serde(with = "...")]` (plus its `option` and
+// `vec` submodules) for a `time` format description, and a `Format` marker
+// type inside it for use with `Formatted`:
+//
+//     serde_utils::jsontime_format!(
+//         pub local_format,
+//         "[year]-[month]-[day] [hour]:[minute] [offset_hour sign:mandatory]:[offset_minute]"
+//     );
+//
+// The description is parsed once, on first use; an invalid one surfaces as a
+// (de)serialization error.
+#[macro_export]
+macro_rules! jsontime_format {
+    ($vis:vis $name:ident, $description:literal) => {
+        $vis mod $name {
+            pub enum Format {}
+
+            impl Format {
+                fn description() -> Result<
+                    &'static $crate::__private::time::format_description::OwnedFormatItem,
+                    $crate::__private::time::Error,
+                > {
+                    static DESCRIPTION: ::std::sync::OnceLock<
+                        Result<
+                            $crate::__private::time::format_description::OwnedFormatItem,
+                            $crate::__private::time::error::InvalidFormatDescription,
+                        >,
+                    > = ::std::sync::OnceLock::new();
+                    $crate::jsontime::cached_format_description(&DESCRIPTION, $description)
+                }
+            }
+
+            impl $crate::jsontime::Format for Format {
+                const EXPECTING: &'static str = concat!("a date-time formatted as ", $description);
+
+                fn format(
+                    datetime: $crate::__private::time::OffsetDateTime,
+                ) -> Result<String, $crate::__private::time::Error> {
+                    Ok(datetime.format(Self::description()?)?)
+                }
+
+                fn parse(
+                    s: &str,
+                ) -> Result<$crate::__private::time::OffsetDateTime, $crate::__private::time::Error>
+                {
+                    Ok($crate::__private::time::OffsetDateTime::parse(s, Self::description()?)?)
+                }
+            }
+
+            $crate::__jsontime_with_module_body!(
+                <T> $crate::jsontime::Formatted<Format, $crate::jsontime::PreserveOffset, T>
+            );
+
+            pub mod utc {
+                $crate::__jsontime_with_module_body!(
+                    <T> $crate::jsontime::Formatted<super::Format, $crate::jsontime::ToUtc, T>
+                );
+            }
+        }
+    };
+}
+
+// What offset a date-time is written with.
+pub trait OutputOffset {
+    fn apply(datetime: OffsetDateTime) -> OffsetDateTime;
+}
+
+// Keep whatever offset the value carries.
+#[derive(Clone, Copy, Debug)]
+pub enum PreserveOffset {}
+
+impl OutputOffset for PreserveOffset {
+    fn apply(datetime: OffsetDateTime) -> OffsetDateTime {
+        datetime
+    }
+}
+
+// Convert to UTC before formatting.
+#[derive(Clone, Copy, Debug)]
+pub enum ToUtc {}
+
+impl OutputOffset for ToUtc {
+    fn apply(datetime: OffsetDateTime) -> OffsetDateTime {
+        datetime.to_offset(UtcOffset::UTC)
+    }
+}
+
+// Non-`OffsetDateTime` values are converted through `OffsetDateTime`, so they
+// are limited to its range.
+pub struct Formatted<F, O = PreserveOffset, T = OffsetDateTime>(pub T, PhantomData<fn() -> (F, O)>);
+
+impl<F, O, T> Formatted<F, O, T> {
+    pub const fn new(datetime: T) -> Self {
+        Self(datetime, PhantomData)
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+struct FormattedVisitor<F>(PhantomData<fn() -> F>);
+
+impl<'de, F: Format> Visitor<'de> for FormattedVisitor<F> {
+    type Value = OffsetDateTime;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(F::EXPECTING)
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        F::parse(v).map_err(|err| {
+            E::custom(format_args!(
+                "invalid value: string {:?}, expected {}: {}",
+                v,
+                F::EXPECTING,
+                err
+            ))
+        })
+    }
+
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        let s =
+            std::str::from_utf8(v).map_err(|_| E::invalid_value(Unexpected::Bytes(v), &self))?;
+        self.visit_str(s)
+    }
+}
+
+impl<'de, F: Format, O, T: DateTimeValue> serde::Deserialize<'de> for Formatted<F, O, T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let datetime = deserializer.deserialize_str(FormattedVisitor::<F>(PhantomData))?;
+        T::from_offset_datetime(datetime)
+            .map(Self::new)
+            .map_err(D::Error::custom)
+    }
+}
+
+impl<F: Format, O: OutputOffset, T: DateTimeValue> serde::Serialize for Formatted<F, O, T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::Error;
+
+        let datetime = self.0.to_offset_datetime().map_err(S::Error::custom)?;
+        let formatted = F::format(O::apply(datetime)).map_err(S::Error::custom)?;
+        serializer.serialize_str(&formatted)
+    }
+}
+
+crate::impl_wrapper_traits!(Formatted<F, O, T>, T);
+
+impl<F, O> From<Formatted<F, O>> for OffsetDateTime {
+    fn from(formatted: Formatted<F, O>) -> Self {
+        formatted.0
+    }
+}
@@ -0,0 +1,243 @@
+use std::{fmt, marker::PhantomData};
+
+use serde::{
+    de::{Error, IgnoredAny, MapAccess, Unexpected, Visitor},
+    ser::SerializeStruct,
+};
+use time::{OffsetDateTime, UtcOffset};
+
+use super::{DateTimeValue, Int, Repr, Seconds, Unit, UnixTimestamp};
+
+// "+05:30", or "+05:30:15" for offsets with seconds.
+struct OffsetDisplay(UtcOffset);
+
+impl fmt::Display for OffsetDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (hours, minutes, seconds) = self.0.as_hms();
+        let sign = if self.0.is_negative() { '-' } else { '+' };
+        write!(
+            f,
+            "{}{:02}:{:02}",
+            sign,
+            hours.unsigned_abs(),
+            minutes.unsigned_abs()
+        )?;
+        if seconds != 0 {
+            write!(f, ":{:02}", seconds.unsigned_abs())?;
+        }
+        Ok(())
+    }
+}
+
+// Accepts "Z", "+05:30", "+0530", "+05" and "-03:00:15".
+fn parse_offset(s: &str) -> Option<UtcOffset> {
+    if s.eq_ignore_ascii_case("z") {
+        return Some(UtcOffset::UTC);
+    }
+    let negative = match s.as_bytes().first()? {
+        b'+' => false,
+        b'-' => true,
+        _ => return None,
+    };
+    let rest = &s[1..];
+    let digits: String = rest.chars().filter(|&c| c != ':').collect();
+    let separated = rest.contains(':');
+    if !digits.bytes().all(|b| b.is_ascii_digit())
+        || !matches!(digits.len(), 2 | 4 | 6)
+        || separated && rest.split(':').any(|part| part.len() != 2)
+    {
+        return None;
+    }
+    let part = |i: usize| digits.get(i..i + 2).map_or(Ok(0), str::parse::<i8>);
+    let (hours, minutes, seconds) = (part(0).ok()?, part(2).ok()?, part(4).ok()?);
+    let sign = if negative { -1 } else { 1 };
+    UtcOffset::from_hms(sign * hours, sign * minutes, sign * seconds).ok()
+}
+
+struct OffsetVisitor;
+
+impl<'de> Visitor<'de> for OffsetVisitor {
+    type Value = UtcOffset;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a UTC offset such as \"+05:30\" or a number of seconds")
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        parse_offset(v.trim()).ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+    }
+
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> {
+        i32::try_from(v)
+            .ok()
+            .and_then(|seconds| UtcOffset::from_whole_seconds(seconds).ok())
+            .ok_or_else(|| E::invalid_value(Unexpected::Signed(v), &self))
+    }
+
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> {
+        i64::try_from(v)
+            .map_err(|_| E::invalid_value(Unexpected::Unsigned(v), &self))
+            .and_then(|v| self.visit_i64(v))
+    }
+}
+
+struct Offset(UtcOffset);
+
+impl<'de> serde::Deserialize<'de> for Offset {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(OffsetVisitor).map(Offset)
+    }
+}
+
+// `{ "ts": 1700000000, "offset": "+05:30" }`: a unix timestamp (in `U`,
+// written as `R`) together with the offset the value carries. A missing or
+// null offset reads as UTC. Use `rfc3339` for a single string that keeps the
+// offset.
+pub struct OffsetTimestamp<U = Seconds, R = Int, T = OffsetDateTime>(
+    pub T,
+    PhantomData<fn() -> (U, R)>,
+);
+
+impl<U, R, T> OffsetTimestamp<U, R, T> {
+    pub const fn new(datetime: T) -> Self {
+        Self(datetime, PhantomData)
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+const FIELDS: &[&str] = &["ts", "offset"];
+
+enum Field {
+    Ts,
+    Offset,
+    Other,
+}
+
+impl<'de> serde::Deserialize<'de> for Field {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct FieldVisitor;
+
+        impl Visitor<'_> for FieldVisitor {
+            type Value = Field;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a field name")
+            }
+
+            fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(match v {
+                    "ts" => Field::Ts,
+                    "offset" => Field::Offset,
+                    _ => Field::Other,
+                })
+            }
+        }
+
+        deserializer.deserialize_identifier(FieldVisitor)
+    }
+}
+
+struct OffsetTimestampVisitor<U, R>(PhantomData<fn() -> (U, R)>);
+
+impl<'de, U: Unit, R: Repr> Visitor<'de> for OffsetTimestampVisitor<U, R> {
+    type Value = OffsetDateTime;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "an object with a unix timestamp in {} (\"ts\") and a UTC offset (\"offset\")",
+            U::NAME
+        )
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut ts: Option<UnixTimestamp<U, R>> = None;
+        let mut offset: Option<Option<Offset>> = None;
+        while let Some(key) = map.next_key::<Field>()? {
+            match key {
+                Field::Ts if ts.is_some() => return Err(A::Error::duplicate_field("ts")),
+                Field::Ts => ts = Some(map.next_value()?),
+                Field::Offset if offset.is_some() => {
+                    return Err(A::Error::duplicate_field("offset"))
+                }
+                Field::Offset => offset = Some(map.next_value()?),
+                Field::Other => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        let ts = ts.ok_or_else(|| A::Error::missing_field("ts"))?;
+        let offset = offset.flatten().map_or(UtcOffset::UTC, |offset| offset.0);
+        ts.0.checked_to_offset(offset).ok_or_else(|| {
+            A::Error::custom(format_args!(
+                "{} at offset {} is out of range",
+                ts.0, offset
+            ))
+        })
+    }
+}
+
+impl<'de, U: Unit, R: Repr, T: DateTimeValue> serde::Deserialize<'de> for OffsetTimestamp<U, R, T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let datetime = deserializer.deserialize_struct(
+            "OffsetTimestamp",
+            FIELDS,
+            OffsetTimestampVisitor::<U, R>(PhantomData),
+        )?;
+        T::from_offset_datetime(datetime)
+            .map(Self::new)
+            .map_err(D::Error::custom)
+    }
+}
+
+impl<U: Unit, R: Repr, T: DateTimeValue> serde::Serialize for OffsetTimestamp<U, R, T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let datetime = self
+            .0
+            .to_offset_datetime()
+            .map_err(serde::ser::Error::custom)?;
+        let mut state = serializer.serialize_struct("OffsetTimestamp", 2)?;
+        state.serialize_field("ts", &UnixTimestamp::<U, R>::new(datetime))?;
+        state.serialize_field(
+            "offset",
+            &format_args!("{}", OffsetDisplay(datetime.offset())),
+        )?;
+        state.end()
+    }
+}
+
+crate::impl_wrapper_traits!(OffsetTimestamp<U, R, T>, T);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offsets_past_the_range_fail() {
+        let read = |json| serde_json::from_str::<OffsetTimestamp>(json);
+        let max = read(r#"{"ts": 253402300799, "offset": "+00:00"}"#).unwrap();
+        assert_eq!(max.0.unix_timestamp(), 253_402_300_799);
+        assert!(read(r#"{"ts": 253402300799, "offset": "+05:00"}"#).is_err());
+        assert!(read(r#"{"ts": -377705116800, "offset": "-05:00"}"#).is_err());
+        let local = read(r#"{"ts": 0, "offset": "+05:30"}"#).unwrap();
+        assert_eq!(local.0.offset().as_hms(), (5, 30, 0));
+    }
+}
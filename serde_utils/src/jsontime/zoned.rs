@@ -0,0 +1,351 @@
+use std::{fmt, marker::PhantomData};
+
+use serde::de::Error;
+use time::{Duration, OffsetDateTime, PrimitiveDateTime};
+use time_tz::{timezones, Offset, OffsetResult, PrimitiveDateTimeExt, TimeZone, Tz};
+
+use super::{DateTimeValue, NaiveValue, NaiveVisitor};
+
+// An IANA time zone, by name. See `jsontime_timezone!`.
+pub trait ZoneName {
+    const NAME: &'static str;
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LocalTimeError {
+    UnknownTimeZone(&'static str),
+    // The wall-clock time falls into a DST gap ("02:30" on a spring-forward
+    // night).
+    Skipped {
+        local: PrimitiveDateTime,
+        zone: &'static str,
+    },
+    // The wall-clock time happens twice ("02:30" on a fall-back night).
+    Ambiguous {
+        local: PrimitiveDateTime,
+        zone: &'static str,
+    },
+}
+
+impl fmt::Display for LocalTimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownTimeZone(zone) => write!(f, "unknown time zone {:?}", zone),
+            Self::Skipped { local, zone } => write!(
+                f,
+                "{} does not exist in {} (skipped by a DST transition)",
+                DisplayLocal(local),
+                zone
+            ),
+            Self::Ambiguous { local, zone } => write!(
+                f,
+                "{} is ambiguous in {} (repeated by a DST transition)",
+                DisplayLocal(local),
+                zone
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LocalTimeError {}
+
+struct DisplayLocal<'a>(&'a PrimitiveDateTime);
+
+impl fmt::Display for DisplayLocal<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match NaiveValue::format(self.0) {
+            Ok(formatted) => f.write_str(&formatted),
+            Err(_) => fmt::Display::fmt(self.0, f),
+        }
+    }
+}
+
+pub fn time_zone(name: &'static str) -> Result<&'static Tz, LocalTimeError> {
+    timezones::get_by_name(name).ok_or(LocalTimeError::UnknownTimeZone(name))
+}
+
+// What a wall-clock time maps to in a time zone.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LocalTime {
+    Unique(OffsetDateTime),
+    // In a fall-back overlap: the first and the second occurrence.
+    Ambiguous {
+        earlier: OffsetDateTime,
+        later: OffsetDateTime,
+    },
+    // In a spring-forward gap: the time read with the offset from after the
+    // transition (which lands before the gap) and with the offset from before
+    // it (which lands after the gap, i.e. shifted forward by the gap's length).
+    Skipped {
+        earlier: OffsetDateTime,
+        later: OffsetDateTime,
+    },
+}
+
+pub fn resolve_local(local: PrimitiveDateTime, tz: &Tz) -> LocalTime {
+    match PrimitiveDateTimeExt::assume_timezone(&local, tz) {
+        OffsetResult::Some(datetime) => LocalTime::Unique(datetime),
+        OffsetResult::Ambiguous(a, b) => LocalTime::Ambiguous {
+            earlier: a.min(b),
+            later: a.max(b),
+        },
+        OffsetResult::None => {
+            let instant = local.assume_utc();
+            let before = tz.get_offset_utc(&(instant - Duration::DAY)).to_utc();
+            let after = tz.get_offset_utc(&(instant + Duration::DAY)).to_utc();
+            LocalTime::Skipped {
+                earlier: local.assume_offset(after).to_offset(before),
+                later: local.assume_offset(before).to_offset(after),
+            }
+        }
+    }
+}
+
+// How `Zoned` picks an instant for wall-clock times in DST gaps and overlaps.
+pub trait Disambiguation {
+    fn resolve(
+        local: PrimitiveDateTime,
+        zone: &'static str,
+        resolved: LocalTime,
+    ) -> Result<OffsetDateTime, LocalTimeError>;
+}
+
+// Fail on both gaps and overlaps.
+#[derive(Clone, Copy, Debug)]
+pub enum Reject {}
+
+impl Disambiguation for Reject {
+    fn resolve(
+        local: PrimitiveDateTime,
+        zone: &'static str,
+        resolved: LocalTime,
+    ) -> Result<OffsetDateTime, LocalTimeError> {
+        match resolved {
+            LocalTime::Unique(datetime) => Ok(datetime),
+            LocalTime::Ambiguous { .. } => Err(LocalTimeError::Ambiguous { local, zone }),
+            LocalTime::Skipped { .. } => Err(LocalTimeError::Skipped { local, zone }),
+        }
+    }
+}
+
+// Take the earlier candidate in both cases.
+#[derive(Clone, Copy, Debug)]
+pub enum Earlier {}
+
+impl Disambiguation for Earlier {
+    fn resolve(
+        _local: PrimitiveDateTime,
+        _zone: &'static str,
+        resolved: LocalTime,
+    ) -> Result<OffsetDateTime, LocalTimeError> {
+        match resolved {
+            LocalTime::Unique(datetime) => Ok(datetime),
+            LocalTime::Ambiguous { earlier, .. } | LocalTime::Skipped { earlier, .. } => {
+                Ok(earlier)
+            }
+        }
+    }
+}
+
+// Take the later candidate in both cases.
+#[derive(Clone, Copy, Debug)]
+pub enum Later {}
+
+impl Disambiguation for Later {
+    fn resolve(
+        _local: PrimitiveDateTime,
+        _zone: &'static str,
+        resolved: LocalTime,
+    ) -> Result<OffsetDateTime, LocalTimeError> {
+        match resolved {
+            LocalTime::Unique(datetime) => Ok(datetime),
+            LocalTime::Ambiguous { later, .. } | LocalTime::Skipped { later, .. } => Ok(later),
+        }
+    }
+}
+
+// What calendaring software usually does (RFC 5545): the first occurrence in
+// an overlap, and shift forward by the gap's length in a gap.
+#[derive(Clone, Copy, Debug)]
+pub enum Compatible {}
+
+impl Disambiguation for Compatible {
+    fn resolve(
+        _local: PrimitiveDateTime,
+        _zone: &'static str,
+        resolved: LocalTime,
+    ) -> Result<OffsetDateTime, LocalTimeError> {
+        match resolved {
+            LocalTime::Unique(datetime) => Ok(datetime),
+            LocalTime::Ambiguous { earlier, .. } => Ok(earlier),
+            LocalTime::Skipped { later, .. } => Ok(later),
+        }
+    }
+}
+
+// A date-time written as wall-clock time in the time zone `Z`, without an
+// offset: "2024-02-07T14:30:00". Output is the wall-clock time of the value in
+// `Z`, so an instant inside an overlap reads back according to `P`.
+pub struct Zoned<Z, P = Reject, T = OffsetDateTime>(pub T, PhantomData<fn() -> (Z, P)>);
+
+impl<Z, P, T> Zoned<Z, P, T> {
+    pub const fn new(datetime: T) -> Self {
+        Self(datetime, PhantomData)
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<'de, Z: ZoneName, P: Disambiguation, T: DateTimeValue> serde::Deserialize<'de>
+    for Zoned<Z, P, T>
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let local: PrimitiveDateTime = deserializer.deserialize_str(NaiveVisitor::new())?;
+        let tz = time_zone(Z::NAME).map_err(D::Error::custom)?;
+        let datetime =
+            P::resolve(local, Z::NAME, resolve_local(local, tz)).map_err(D::Error::custom)?;
+        T::from_offset_datetime(datetime)
+            .map(Self::new)
+            .map_err(D::Error::custom)
+    }
+}
+
+impl<Z: ZoneName, P, T: DateTimeValue> serde::Serialize for Zoned<Z, P, T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::Error;
+
+        let tz = time_zone(Z::NAME).map_err(S::Error::custom)?;
+        let datetime = self.0.to_offset_datetime().map_err(S::Error::custom)?;
+        let offset = tz.get_offset_utc(&datetime).to_utc();
+        let datetime = datetime.checked_to_offset(offset).ok_or_else(|| {
+            S::Error::custom(format_args!("{} is out of range in {}", datetime, Z::NAME))
+        })?;
+        let local = PrimitiveDateTime::new(datetime.date(), datetime.time());
+        let formatted = NaiveValue::format(&local).map_err(S::Error::custom)?;
+        serializer.serialize_str(&formatted)
+    }
+}
+
+crate::impl_wrapper_traits!(Zoned<Z, P, T>, T);
+
+// Defines a module usable with `#[serde(with = "...")]` (plus `option` and
+// `vec`) for date-times written as wall-clock time in an IANA time zone, and a
+// `Zone` marker type inside it for use with `Zoned`:
+//
+//     serde_utils::jsontime_timezone!(pub berlin, "Europe/Berlin");
+//
+// The module itself rejects times in DST gaps and overlaps; its `earlier`,
+// `later` and `compatible` submodules resolve them instead.
+#[macro_export]
+macro_rules! jsontime_timezone {
+    ($vis:vis $name:ident, $zone:literal) => {
+        $vis mod $name {
+            pub enum Zone {}
+
+            impl $crate::jsontime::ZoneName for Zone {
+                const NAME: &'static str = $zone;
+            }
+
+            $crate::__jsontime_with_module_body!(
+                <T> $crate::jsontime::Zoned<Zone, $crate::jsontime::Reject, T>
+            );
+
+            pub mod earlier {
+                $crate::__jsontime_with_module_body!(
+                    <T> $crate::jsontime::Zoned<super::Zone, $crate::jsontime::Earlier, T>
+                );
+            }
+
+            pub mod later {
+                $crate::__jsontime_with_module_body!(
+                    <T> $crate::jsontime::Zoned<super::Zone, $crate::jsontime::Later, T>
+                );
+            }
+
+            pub mod compatible {
+                $crate::__jsontime_with_module_body!(
+                    <T> $crate::jsontime::Zoned<super::Zone, $crate::jsontime::Compatible, T>
+                );
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    enum NewYork {}
+
+    impl ZoneName for NewYork {
+        const NAME: &'static str = "America/New_York";
+    }
+
+    enum Tokyo {}
+
+    impl ZoneName for Tokyo {
+        const NAME: &'static str = "Asia/Tokyo";
+    }
+
+    fn write<Z: ZoneName>(datetime: OffsetDateTime) -> Result<String, serde_json::Error> {
+        serde_json::to_string(&Zoned::<Z>::new(datetime))
+    }
+
+    fn read<Z: ZoneName, P: Disambiguation>(json: &str) -> Result<OffsetDateTime, String> {
+        serde_json::from_str::<Zoned<Z, P>>(json)
+            .map(Zoned::into_inner)
+            .map_err(|err| err.to_string())
+    }
+
+    #[test]
+    fn ends_of_the_range() {
+        let max = PrimitiveDateTime::MAX.assume_utc();
+        let min = PrimitiveDateTime::MIN.assume_utc();
+        assert!(write::<Tokyo>(max).is_err());
+        assert!(write::<NewYork>(min).is_err());
+        assert!(write::<NewYork>(max).is_ok());
+        assert!(write::<Tokyo>(min).is_ok());
+        // Wall-clock times at the ends stay readable, keeping their offset
+        // even when the instant in UTC is past the range.
+        assert_eq!(
+            read::<NewYork, Reject>(r#""9999-12-31T23:00:00""#),
+            Ok(datetime!(9999-12-31 23:00 -5))
+        );
+    }
+
+    #[test]
+    fn dst_transitions() {
+        // 2024-03-10 02:30 doesn't exist in New York, 2024-11-03 01:30 happens
+        // twice.
+        let skipped = r#""2024-03-10T02:30:00""#;
+        let repeated = r#""2024-11-03T01:30:00""#;
+        assert!(read::<NewYork, Reject>(skipped).is_err());
+        assert!(read::<NewYork, Reject>(repeated).is_err());
+        assert_eq!(
+            read::<NewYork, Earlier>(repeated),
+            Ok(datetime!(2024-11-03 01:30 -4))
+        );
+        assert_eq!(
+            read::<NewYork, Later>(repeated),
+            Ok(datetime!(2024-11-03 01:30 -5))
+        );
+        assert_eq!(
+            read::<NewYork, Later>(skipped),
+            Ok(datetime!(2024-03-10 03:30 -4))
+        );
+        assert_eq!(
+            write::<NewYork>(datetime!(2024-07-01 12:00 UTC)).unwrap(),
+            r#""2024-07-01T08:00:00""#
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use std::{cell::RefCell, fmt};
+
+// Adapters for dirty input: each one turns a bad value into something usable
+// instead of failing the whole document. What `skip_invalid` and
+// `default_on_error` recover from is recorded, and can be picked up around
+// the call that deserializes the document:
+//
+//     let (page, errors) = lenient::collect_recovered(|| serde_json::from_str::<Page>(&json));
+
+// A value an adapter replaced or left out, with `index` set for sequence
+// elements.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecoveredError {
+    index: Option<usize>,
+    message: String,
+}
+
+impl RecoveredError {
+    pub fn index(&self) -> Option<usize> {
+        self.index
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for RecoveredError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.index {
+            Some(index) => write!(f, "item {}: {}", index, self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl std::error::Error for RecoveredError {}
+
+thread_local! {
+    static RECOVERED: RefCell<Option<Vec<RecoveredError>>> = const { RefCell::new(None) };
+}
+
+fn record(index: Option<usize>, error: impl fmt::Display) {
+    RECOVERED.with(|recovered| {
+        if let Some(errors) = recovered.borrow_mut().as_mut() {
+            errors.push(RecoveredError {
+                index,
+                message: error.to_string(),
+            });
+        }
+    });
+}
+
+// Puts back the collector of an enclosing `collect_recovered`, even when
+// the closure panics.
+struct Restore(Option<Vec<RecoveredError>>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        RECOVERED.with(|recovered| *recovered.borrow_mut() = self.0.take());
+    }
+}
+
+// Runs `f` and returns the errors the adapters recovered from on this thread
+// meanwhile. Outside of it they are dropped.
+pub fn collect_recovered<R>(f: impl FnOnce() -> R) -> (R, Vec<RecoveredError>) {
+    let _restore = Restore(RECOVERED.with(|recovered| recovered.replace(Some(Vec::new()))));
+    let result = f();
+    let errors = RECOVERED.with(|recovered| recovered.borrow_mut().take());
+    (result, errors.unwrap_or_default())
+}
+
+// The adapters only change how values are read; they are written as usual.
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: serde::Serialize,
+    S: serde::Serializer,
+{
+    value.serialize(serializer)
+}
+
+// `Option<T>` with "" as `None`, as well as null.
+pub mod empty_string_as_none {
+    use serde::{de::Error, Deserialize};
+    use serde_value::Value;
+
+    pub use super::serialize;
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        T: serde::Deserialize<'de>,
+    {
+        let mut value = Value::deserialize(deserializer)?;
+        while let Value::Option(Some(inner)) | Value::Newtype(inner) = value {
+            value = *inner;
+        }
+        match value {
+            Value::Unit | Value::Option(None) => Ok(None),
+            Value::String(s) if s.is_empty() => Ok(None),
+            value => T::deserialize(value).map(Some).map_err(D::Error::custom),
+        }
+    }
+}
+
+// `T` with null as `T::default()`.
+pub mod null_as_default {
+    use serde::Deserialize;
+
+    pub use super::serialize;
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        T: serde::Deserialize<'de> + Default,
+    {
+        Option::<T>::deserialize(deserializer).map(Option::unwrap_or_default)
+    }
+}
+
+// A `Vec<T>` or other collection without the elements that don't read as
+// a `T`; each one left out is recorded.
+pub mod skip_invalid {
+    use serde::Deserialize;
+    use serde_value::Value;
+
+    pub use super::serialize;
+
+    pub fn deserialize<'de, C, T, D>(deserializer: D) -> Result<C, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        C: IntoIterator<Item = T> + FromIterator<T>,
+        T: serde::Deserialize<'de>,
+    {
+        let values = Vec::<Value>::deserialize(deserializer)?;
+        Ok(values
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, value)| match T::deserialize(value) {
+                Ok(value) => Some(value),
+                Err(err) => {
+                    super::record(Some(index), err);
+                    None
+                }
+            })
+            .collect())
+    }
+}
+
+// `T::default()` in place of a value that doesn't read as a `T`, recording
+// the error. Input that isn't well-formed still fails.
+pub mod default_on_error {
+    use serde::Deserialize;
+    use serde_value::Value;
+
+    pub use super::serialize;
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        T: serde::Deserialize<'de> + Default,
+    {
+        let value = Value::deserialize(deserializer)?;
+        Ok(T::deserialize(value).unwrap_or_else(|err| {
+            super::record(None, err);
+            T::default()
+        }))
+    }
+}
@@ -1,2 +1,100 @@
+// The usual traits of a wrapper newtype, forwarded to the value it wraps:
+// `$inner` names the parameter for that value, the others are markers that
+// need no bounds.
+macro_rules! impl_wrapper_traits {
+    ($wrapper:ident<$($param:ident),+>, $inner:ident) => {
+        impl<$($param),+> From<$inner> for $wrapper<$($param),+> {
+            fn from(value: $inner) -> Self {
+                Self::new(value)
+            }
+        }
+
+        impl<$($param),+> Clone for $wrapper<$($param),+>
+        where
+            $inner: Clone,
+        {
+            fn clone(&self) -> Self {
+                Self::new(self.0.clone())
+            }
+        }
+
+        impl<$($param),+> Copy for $wrapper<$($param),+> where $inner: Copy {}
+
+        impl<$($param),+> std::fmt::Debug for $wrapper<$($param),+>
+        where
+            $inner: std::fmt::Debug,
+        {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_tuple(stringify!($wrapper)).field(&self.0).finish()
+            }
+        }
+
+        impl<$($param),+> PartialEq for $wrapper<$($param),+>
+        where
+            $inner: PartialEq,
+        {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+
+        impl<$($param),+> Eq for $wrapper<$($param),+> where $inner: Eq {}
+
+        impl<$($param),+> PartialOrd for $wrapper<$($param),+>
+        where
+            $inner: PartialOrd,
+        {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                self.0.partial_cmp(&other.0)
+            }
+        }
+
+        impl<$($param),+> Ord for $wrapper<$($param),+>
+        where
+            $inner: Ord,
+        {
+            fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+                self.0.cmp(&other.0)
+            }
+        }
+
+        impl<$($param),+> std::hash::Hash for $wrapper<$($param),+>
+        where
+            $inner: std::hash::Hash,
+        {
+            fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                self.0.hash(state)
+            }
+        }
+    };
+}
+
+pub(crate) use impl_wrapper_traits;
+
+pub mod binary;
 pub mod enumstrings;
 pub mod jsontime;
+pub mod lenient;
+
+#[cfg(feature = "derive")]
+pub use serde_utils_derive::{DeserializeFromStr, EnumStr, SerializeDisplay};
+
+#[doc(hidden)]
+pub mod __private {
+    pub use serde;
+    pub use time;
+
+    // Implemented by the derives that own container options, so the others
+    // can check they are on the type as well.
+    #[cfg(feature = "derive")]
+    #[diagnostic::on_unimplemented(
+        message = "`expecting` and `error` in `#[serde_utils(...)]` need `#[derive(DeserializeFromStr)]` on `{Self}`"
+    )]
+    pub trait DeserializeFromStrDerived {}
+
+    #[cfg(feature = "derive")]
+    #[diagnostic::on_unimplemented(
+        message = "`rename_all` in `#[serde_utils(...)]` needs `#[derive(EnumStr)]` on `{Self}`"
+    )]
+    pub trait EnumStrDerived {}
+}
@@ -0,0 +1,174 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, Ident, LitStr, Variant};
+
+use crate::{Derive, Options};
+
+fn words(ident: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    for (i, c) in ident.char_indices() {
+        if c == '_' {
+            words.push(String::new());
+        } else if c.is_uppercase() && i > 0 || words.is_empty() {
+            words.push(c.to_string());
+        } else if let Some(last) = words.last_mut() {
+            last.push(c);
+        }
+    }
+    words.retain(|word| !word.is_empty());
+    words
+}
+
+fn rename(ident: &Ident, rule: Option<&LitStr>) -> syn::Result<String> {
+    let name = ident.to_string();
+    let Some(rule) = rule else {
+        return Ok(name);
+    };
+    let lower = || words(&name).join("_").to_lowercase();
+    Ok(match rule.value().as_str() {
+        "lowercase" => name.to_lowercase(),
+        "UPPERCASE" => name.to_uppercase(),
+        "snake_case" => lower(),
+        "SCREAMING_SNAKE_CASE" => lower().to_uppercase(),
+        "kebab-case" => lower().replace('_', "-"),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                rule,
+                "expected one of \"lowercase\", \"UPPERCASE\", \"snake_case\", \
+                 \"SCREAMING_SNAKE_CASE\" or \"kebab-case\"",
+            ))
+        }
+    })
+}
+
+struct VariantOptions {
+    rename: Option<LitStr>,
+    aliases: Vec<LitStr>,
+    other: bool,
+}
+
+impl VariantOptions {
+    fn from_variant(variant: &Variant) -> syn::Result<Self> {
+        let mut options = Self {
+            rename: None,
+            aliases: Vec::new(),
+            other: false,
+        };
+        for attr in variant
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("serde_utils"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    options.rename = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("alias") {
+                    options.aliases.push(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("other") {
+                    options.other = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `rename`, `alias` or `other`"))
+                }
+            })?;
+        }
+        Ok(options)
+    }
+}
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "`EnumStr` only supports enums",
+        ));
+    };
+    let options = Options::from_input(&input)?;
+    let krate = options.krate();
+    let checks = options.check_derives(&input, Derive::EnumStr);
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut accepted = Vec::new();
+    let mut parse_arms = Vec::new();
+    let mut display_arms = Vec::new();
+    let mut other = None;
+    for variant in &data.variants {
+        let variant_options = VariantOptions::from_variant(variant)?;
+        let ident = &variant.ident;
+
+        if variant_options.other {
+            let single_field =
+                matches!(&variant.fields, Fields::Unnamed(fields) if fields.unnamed.len() == 1);
+            if !single_field || other.is_some() {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "`other` goes on a single variant with one field, like `Unknown(String)`",
+                ));
+            }
+            other = Some(ident);
+            display_arms.push(quote! {
+                Self::#ident(value) => ::std::fmt::Display::fmt(value, f),
+            });
+            continue;
+        }
+
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "`EnumStr` variants must be unit variants, apart from the `other` one",
+            ));
+        }
+        let canonical = match &variant_options.rename {
+            Some(rename) => rename.value(),
+            None => rename(ident, options.rename_all.as_ref())?,
+        };
+        let names: Vec<String> = std::iter::once(canonical.clone())
+            .chain(variant_options.aliases.iter().map(LitStr::value))
+            .collect();
+        parse_arms.push(quote! {
+            if #(s.eq_ignore_ascii_case(#names))||* {
+                return ::std::result::Result::Ok(Self::#ident);
+            }
+        });
+        display_arms.push(quote! {
+            Self::#ident => f.write_str(#canonical),
+        });
+        accepted.extend(names);
+    }
+
+    let fallback = match other {
+        Some(ident) => quote! {
+            ::std::result::Result::Ok(Self::#ident(::std::convert::From::from(s)))
+        },
+        None => quote! {
+            const ACCEPTED: &[&str] = &[#(#accepted),*];
+            ::std::result::Result::Err(#krate::enumstrings::UnknownVariantError::new(
+                s, ACCEPTED,
+            ))
+        },
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::std::str::FromStr for #name #ty_generics #where_clause {
+            type Err = #krate::enumstrings::UnknownVariantError;
+
+            fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                #(#parse_arms)*
+                #fallback
+            }
+        }
+
+        impl #impl_generics ::std::fmt::Display for #name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                match self {
+                    #(#display_arms)*
+                }
+            }
+        }
+
+        #checks
+    })
+}
@@ -0,0 +1,217 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{quote, quote_spanned};
+use syn::{parse_macro_input, parse_quote, DeriveInput, Ident, LitStr, Path};
+
+mod enum_str;
+
+// The derives sharing the `#[serde_utils(...)]` container attribute.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Derive {
+    DeserializeFromStr,
+    SerializeDisplay,
+    EnumStr,
+}
+
+impl Derive {
+    // The hidden trait the derive implements, so that the others can check
+    // it is there.
+    fn marker(self) -> Option<&'static str> {
+        match self {
+            Self::DeserializeFromStr => Some("DeserializeFromStrDerived"),
+            Self::SerializeDisplay => None,
+            Self::EnumStr => Some("EnumStrDerived"),
+        }
+    }
+}
+
+// Container options from `#[serde_utils(...)]`, shared by all derives:
+// `expecting = "..."` and `error = "..."` for `DeserializeFromStr` (`error`
+// may use `{value}` and `{error}` for the input string and the `FromStr`
+// error), `rename_all = "..."` for `EnumStr`, and `crate = "..."` for all of
+// them when `serde_utils` is not at `::serde_utils`. An option for a derive
+// that isn't on the type fails to compile.
+#[derive(Default)]
+struct Options {
+    expecting: Option<LitStr>,
+    error: Option<LitStr>,
+    rename_all: Option<LitStr>,
+    krate: Option<Path>,
+}
+
+impl Options {
+    fn from_input(input: &DeriveInput) -> syn::Result<Self> {
+        let mut options = Self::default();
+        for attr in input
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("serde_utils"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("expecting") {
+                    options.expecting = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("error") {
+                    options.error = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("rename_all") {
+                    options.rename_all = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("crate") {
+                    let path: LitStr = meta.value()?.parse()?;
+                    options.krate = Some(path.parse()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `expecting`, `error`, `rename_all` or `crate`"))
+                }
+            })?;
+        }
+        Ok(options)
+    }
+
+    fn krate(&self) -> Path {
+        self.krate
+            .clone()
+            .unwrap_or_else(|| parse_quote!(::serde_utils))
+    }
+
+    // Implements this derive's marker trait, and checks that the derives
+    // owning the other options given are on the type too.
+    fn check_derives(&self, input: &DeriveInput, derive: Derive) -> TokenStream2 {
+        let krate = self.krate();
+        let name = &input.ident;
+        let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+        let marker_impl = derive.marker().map(|marker| {
+            let marker = Ident::new(marker, Span::call_site());
+            quote! {
+                impl #impl_generics #krate::__private::#marker for #name #ty_generics #where_clause {}
+            }
+        });
+
+        let foreign = [
+            (&self.expecting, Derive::DeserializeFromStr),
+            (&self.error, Derive::DeserializeFromStr),
+            (&self.rename_all, Derive::EnumStr),
+        ];
+        let checks = foreign
+            .into_iter()
+            .filter(|(option, owner)| option.is_some() && *owner != derive)
+            .filter_map(|(option, owner)| {
+                let marker = Ident::new(owner.marker()?, option.as_ref()?.span());
+                Some(quote_spanned! {marker.span()=>
+                    const _: () = {
+                        #[allow(dead_code)]
+                        fn check #impl_generics () #where_clause {
+                            fn derived<T: ?Sized + #krate::__private::#marker>() {}
+                            derived::<#name #ty_generics>();
+                        }
+                    };
+                })
+            });
+        quote! {
+            #marker_impl
+            #(#checks)*
+        }
+    }
+}
+
+fn expand_deserialize_from_str(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let options = Options::from_input(&input)?;
+    let krate = options.krate();
+    let checks = options.check_derives(&input, Derive::DeserializeFromStr);
+    let name = &input.ident;
+
+    let mut generics = input.generics.clone();
+    generics.params.insert(0, parse_quote!('de));
+    let (impl_generics, _, _) = generics.split_for_impl();
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut where_clause = where_clause.cloned().unwrap_or_else(|| parse_quote!(where));
+    where_clause
+        .predicates
+        .push(parse_quote!(Self: ::std::str::FromStr));
+    where_clause
+        .predicates
+        .push(parse_quote!(<Self as ::std::str::FromStr>::Err: ::std::fmt::Display));
+
+    let expecting = match &options.expecting {
+        Some(expecting) => quote!(#expecting),
+        None => quote!("a string"),
+    };
+    let error = match &options.error {
+        Some(error) => quote!(::std::option::Option::Some(#error)),
+        None => quote!(::std::option::Option::None),
+    };
+
+    Ok(quote! {
+        impl #impl_generics #krate::__private::serde::Deserialize<'de>
+            for #name #ty_generics #where_clause
+        {
+            fn deserialize<__D>(deserializer: __D) -> ::std::result::Result<Self, __D::Error>
+            where
+                __D: #krate::__private::serde::Deserializer<'de>,
+            {
+                #krate::enumstrings::deserialize_from_str_with(deserializer, #expecting, #error)
+            }
+        }
+
+        #checks
+    })
+}
+
+fn expand_serialize_display(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let options = Options::from_input(&input)?;
+    let krate = options.krate();
+    let checks = options.check_derives(&input, Derive::SerializeDisplay);
+    let name = &input.ident;
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut where_clause = where_clause.cloned().unwrap_or_else(|| parse_quote!(where));
+    where_clause
+        .predicates
+        .push(parse_quote!(Self: ::std::fmt::Display));
+
+    Ok(quote! {
+        impl #impl_generics #krate::__private::serde::Serialize
+            for #name #ty_generics #where_clause
+        {
+            fn serialize<__S>(&self, serializer: __S) -> ::std::result::Result<__S::Ok, __S::Error>
+            where
+                __S: #krate::__private::serde::Serializer,
+            {
+                #krate::enumstrings::serialize_to_string(self, serializer)
+            }
+        }
+
+        #checks
+    })
+}
+
+// `impl Deserialize` through `FromStr`, for any type including generic ones.
+#[proc_macro_derive(DeserializeFromStr, attributes(serde_utils))]
+pub fn derive_deserialize_from_str(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_deserialize_from_str(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+// `impl Serialize` through `Display`, for any type including generic ones.
+#[proc_macro_derive(SerializeDisplay, attributes(serde_utils))]
+pub fn derive_serialize_display(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_serialize_display(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+// `FromStr` and `Display` for enums of unit variants, matching names case
+// insensitively. Variants take `#[serde_utils(rename = "...")]`, any number
+// of `#[serde_utils(alias = "...")]`, and one variant holding a `String` may
+// be marked `#[serde_utils(other)]` to catch everything else.
+#[proc_macro_derive(EnumStr, attributes(serde_utils))]
+pub fn derive_enum_str(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    enum_str::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}